@@ -1,5 +1,6 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use day1_simd_parser::{self, parse_input, solve_puzzle};
+use parse_ints::simd::parse_ints_default;
 use std::hint::black_box;
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -9,6 +10,19 @@ fn criterion_benchmark(c: &mut Criterion) {
     group.bench_function("parse_input_simd_parser", |b| {
         b.iter(|| parse_input(black_box(input)))
     });
+    // The way `parse_input` used to work, for comparison
+    group.bench_function("parse_collect_then_split", |b| {
+        b.iter(|| {
+            let numbers = parse_ints_default(black_box(input));
+            let mut left = Vec::with_capacity(numbers.len() / 2);
+            let mut right = Vec::with_capacity(numbers.len() / 2);
+            for pair in numbers.chunks_exact(2) {
+                left.push(pair[0]);
+                right.push(pair[1]);
+            }
+            (left, right)
+        })
+    });
     group.bench_function("solve_puzzle_simd_parser", |b| {
         b.iter(|| {
             let left = left.clone();
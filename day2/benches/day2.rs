@@ -1,5 +1,6 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use day2::{self, parse_input, solve_puzzle_part1};
+use parse_ints::scalar::parse_until_newline;
 use std::hint::black_box;
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -11,6 +12,15 @@ fn criterion_benchmark(c: &mut Criterion) {
     group.bench_function("parse_input", |b| {
         b.iter(|| parse_input(black_box(input_str)))
     });
+    // The way `parse_input` used to work, for comparison
+    group.bench_function("parse_lines_scalar", |b| {
+        b.iter(|| {
+            black_box(input_str)
+                .split('\n')
+                .map(|line| parse_until_newline(line.as_bytes()))
+                .collect::<Vec<_>>()
+        })
+    });
     group.bench_function("solve_part1", |b| {
         b.iter(|| solve_puzzle_part1(black_box(&lines)))
     });
@@ -1,19 +1,12 @@
-use parse_ints::scalar::parse_until_newline;
+use parse_ints::rows::{parse_rows, RaggedRows};
 
-// For my puzzle input there are 6488 numbers, and this takes 138us.
-// That comes out to ~21ns per number. Not bad, but could probably
-// be better. Maybe don't use the `.split` method and instead write
-// my own loop that uses the number of bytes consumed by the
-// `parse_until_newline` output.
+// For my puzzle input there are 6488 numbers. Splitting on newlines and
+// parsing each line with `scalar::parse_until_newline` takes ~220us, finding
+// the newlines in the same SIMD windows as the digits takes ~23us
+// (`cargo bench -p day2`, `parse_lines_scalar` vs `parse_input`).
 
-pub fn parse_input(puzzle: &str) -> Vec<Vec<u32>> {
-    let output = puzzle
-        .split('\n')
-        .map(|line| parse_until_newline(line.as_bytes()))
-        .collect::<Vec<Vec<_>>>();
-    // let total = output.iter().fold(0, |acc, new| acc + new.len());
-    // eprintln!("{total}");
-    output
+pub fn parse_input(puzzle: &str) -> RaggedRows {
+    parse_rows(puzzle.as_bytes())
 }
 
 fn line_is_safe(orig_line: &[u32]) -> bool {
@@ -30,9 +23,9 @@ fn line_is_safe(orig_line: &[u32]) -> bool {
     valid_increasing || valid_decreasing
 }
 
-pub fn solve_puzzle_part1(lines: &[Vec<u32>]) -> u32 {
+pub fn solve_puzzle_part1(lines: &RaggedRows) -> u32 {
     let mut count = 0;
-    for line in lines.iter() {
+    for line in lines.rows() {
         if line_is_safe(line) {
             count += 1;
         }
@@ -0,0 +1,26 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use parse_ints::{
+    dispatch::{parse_ints_with, Backend},
+    portable,
+    simd::LOOKUP_TABLE,
+};
+use std::hint::black_box;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let input = include_bytes!("../../input/day1.txt");
+    let mut group = c.benchmark_group("parse_ints");
+    for (name, backend) in [("simd_16", Backend::Ssse3), ("avx512_64", Backend::Avx512)] {
+        if !backend.is_supported() {
+            continue;
+        }
+        group.bench_function(name, |b| {
+            b.iter(|| parse_ints_with(backend, black_box(input), &LOOKUP_TABLE))
+        });
+    }
+    group.bench_function("portable_16", |b| {
+        b.iter(|| portable::parse_ints(black_box(input), &LOOKUP_TABLE))
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
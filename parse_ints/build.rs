@@ -0,0 +1,24 @@
+//! Generates the lookup table used by the SIMD parser so that it can be embedded
+//! in the binary instead of being loaded from disk.
+use std::{fmt::Write, path::PathBuf};
+
+#[allow(dead_code)]
+#[path = "src/simd/pattern.rs"]
+mod pattern;
+
+fn main() {
+    println!("cargo::rerun-if-changed=src/simd/pattern.rs");
+    let table = pattern::generate_pattern_lookup_table();
+    let mut source = String::from("[\n");
+    for entry in table.iter() {
+        writeln!(
+            source,
+            "    PatternData {{ shuffle_array: {:?}, skip: {}, n_extracted: {}, conversion_size: {} }},",
+            entry.shuffle_array, entry.skip, entry.n_extracted, entry.conversion_size
+        )
+        .unwrap();
+    }
+    source.push_str("]\n");
+    let out_dir = PathBuf::from(std::env::var_os("OUT_DIR").unwrap());
+    std::fs::write(out_dir.join("lookup_table.rs"), source).unwrap();
+}
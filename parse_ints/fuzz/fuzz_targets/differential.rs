@@ -0,0 +1,15 @@
+//! Feeds arbitrary bytes to every parser and compares them to the reference.
+//!
+//! Run with `cargo fuzz run differential` from `parse_ints`, and shrink a crash with
+//! `cargo fuzz tmin differential <artifact>`.
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../tests/common/differential.rs"]
+mod differential;
+
+fuzz_target!(|bytes: &[u8]| {
+    if let Err(disagreement) = differential::check(bytes) {
+        panic!("{disagreement:?}");
+    }
+});
@@ -0,0 +1,168 @@
+//! Parsing rows of numbers straight into one vector per column.
+use std::{fmt, mem::MaybeUninit};
+
+use crate::{
+    dispatch::{self, Backend},
+    scalar,
+    simd::{avx512, for_each_chunk, PatternData, LOOKUP_TABLE},
+};
+
+/// The error returned when the numbers in the input don't divide evenly into rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RaggedColumns {
+    /// How many numbers were in the input.
+    pub n_numbers: usize,
+    /// How many columns they were supposed to be split into.
+    pub n_columns: usize,
+}
+
+impl fmt::Display for RaggedColumns {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "found {} numbers, which doesn't split evenly into {} columns",
+            self.n_numbers, self.n_columns
+        )
+    }
+}
+
+impl std::error::Error for RaggedColumns {}
+
+/// Parses the integers out of `bytes` and deals them round-robin into `N` columns, so
+/// the `i`th number on each row ends up in the `i`th vector.
+///
+/// Rows aren't detected, so this only checks that the total count of numbers is a
+/// multiple of `N`.
+pub fn parse_columns<const N: usize>(bytes: &[u8]) -> Result<[Vec<u32>; N], RaggedColumns> {
+    const { assert!(N > 0, "can't split numbers into zero columns") };
+    // Every number takes at least two bytes with its separator
+    let capacity = bytes.len() / (2 * N) + 16;
+    let mut columns: [Vec<u32>; N] = std::array::from_fn(|_| Vec::with_capacity(capacity));
+    let n_numbers = match dispatch::backend() {
+        Backend::Scalar => deal(&scalar::parse_ints(bytes), &mut columns),
+        // SAFETY: The dispatcher only picks backends the CPU supports.
+        Backend::Ssse3 => unsafe { parse_columns_ssse3(bytes, &LOOKUP_TABLE, &mut columns) },
+        // SAFETY: The dispatcher only picks backends the CPU supports.
+        Backend::Avx512 => unsafe { parse_columns_avx512(bytes, &mut columns) },
+    };
+    if n_numbers % N != 0 {
+        return Err(RaggedColumns {
+            n_numbers,
+            n_columns: N,
+        });
+    }
+    Ok(columns)
+}
+
+/// Deals `numbers` round-robin into the columns, returning how many there were.
+fn deal<const N: usize>(numbers: &[u32], columns: &mut [Vec<u32>; N]) -> usize {
+    for (i, n) in numbers.iter().enumerate() {
+        columns[i % N].push(*n);
+    }
+    numbers.len()
+}
+
+/// Converts each window with the 16 byte parser and scatters the numbers from the
+/// conversion to their columns, returning how many there were.
+#[target_feature(enable = "ssse3")]
+unsafe fn parse_columns_ssse3<const N: usize>(
+    bytes: &[u8],
+    lookup_table: &[PatternData],
+    columns: &mut [Vec<u32>; N],
+) -> usize {
+    let mut converted = [MaybeUninit::uninit(); 16];
+    let mut n_numbers = 0;
+    let mut column = 0;
+    for_each_chunk(bytes, lookup_table, |chunk| {
+        let n_extracted = chunk.convert_into(bytes, &mut converted);
+        for n in &converted[..n_extracted] {
+            // SAFETY: The first `n_extracted` numbers were written by the conversion.
+            columns[column].push(unsafe { n.assume_init() });
+            column = if column + 1 == N { 0 } else { column + 1 };
+        }
+        n_numbers += n_extracted;
+    });
+    n_numbers
+}
+
+/// Like [`parse_columns_ssse3`], but scatters the numbers from each window of the
+/// 64 byte parser.
+#[target_feature(enable = "avx512f,avx512bw,avx512vbmi,avx512vbmi2")]
+unsafe fn parse_columns_avx512<const N: usize>(bytes: &[u8], columns: &mut [Vec<u32>; N]) -> usize {
+    let mut n_numbers = 0;
+    let mut column = 0;
+    avx512::for_each_window(bytes, |converted| {
+        for n in converted {
+            columns[column].push(*n);
+            column = if column + 1 == N { 0 } else { column + 1 };
+        }
+        n_numbers += converted.len();
+    });
+    n_numbers
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn splits_puzzle_input_into_columns() {
+        let input = include_bytes!("../../input/day1.txt");
+        let numbers = scalar::parse_ints(input);
+        let [left, right] = parse_columns::<2>(input).unwrap();
+        assert_eq!(left.len(), 1000);
+        assert_eq!(left, numbers.iter().step_by(2).copied().collect::<Vec<_>>());
+        assert_eq!(
+            right,
+            numbers
+                .iter()
+                .skip(1)
+                .step_by(2)
+                .copied()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn rejects_incomplete_rows() {
+        let input = b"1 2 3\n4 5 6\n7 8\n";
+        assert_eq!(
+            parse_columns::<3>(input),
+            Err(RaggedColumns {
+                n_numbers: 8,
+                n_columns: 3
+            })
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn avx512_scatters_like_ssse3(input in "[0-9 \n]{0,300}") {
+            if !dispatch::can_test(Backend::Avx512) {
+                return Ok(());
+            }
+            let mut expected: [Vec<u32>; 3] = Default::default();
+            let mut scattered: [Vec<u32>; 3] = Default::default();
+            // SAFETY: We just checked that the CPU supports AVX-512, which implies SSSE3.
+            let n_expected = unsafe {
+                parse_columns_ssse3(input.as_bytes(), &LOOKUP_TABLE, &mut expected)
+            };
+            let n_scattered = unsafe { parse_columns_avx512(input.as_bytes(), &mut scattered) };
+            prop_assert_eq!(n_expected, n_scattered);
+            prop_assert_eq!(expected, scattered);
+        }
+
+        #[test]
+        fn columns_match_rows(rows in prop::collection::vec(prop::array::uniform3(0u32..=99999), 0..100)) {
+            let input: String = rows
+                .iter()
+                .map(|[a, b, c]| format!("{a} {b}   {c}\n"))
+                .collect();
+            let [first, second, third] = parse_columns::<3>(input.as_bytes()).unwrap();
+            prop_assert_eq!(first, rows.iter().map(|row| row[0]).collect::<Vec<_>>());
+            prop_assert_eq!(second, rows.iter().map(|row| row[1]).collect::<Vec<_>>());
+            prop_assert_eq!(third, rows.iter().map(|row| row[2]).collect::<Vec<_>>());
+        }
+    }
+}
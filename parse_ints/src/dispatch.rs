@@ -0,0 +1,184 @@
+//! Runtime selection of the integer parsing backend.
+//!
+//! The SIMD parser uses instructions that not every x86_64 CPU has, so calling
+//! it unconditionally can crash with SIGILL. This module checks which features
+//! the CPU supports the first time it's asked and caches the answer.
+use std::sync::OnceLock;
+
+use crate::scalar;
+use crate::simd::{self, PatternData};
+
+/// Which implementation is used to parse integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Plain byte-at-a-time parsing, runs anywhere.
+    Scalar,
+    /// 16 byte windows using the `pshufb` lookup table.
+    Ssse3,
+    /// 64 byte windows using `vpcompressb` and `vpermb` to gather digits, no lookup table.
+    Avx512,
+}
+
+impl Backend {
+    /// Every backend, in increasing order of preference.
+    pub const ALL: [Backend; 3] = [Backend::Scalar, Backend::Ssse3, Backend::Avx512];
+
+    /// Whether the current CPU can run this backend.
+    pub fn is_supported(self) -> bool {
+        match self {
+            Backend::Scalar => true,
+            Backend::Ssse3 => is_x86_feature_detected!("ssse3"),
+            Backend::Avx512 => {
+                is_x86_feature_detected!("avx512f")
+                    && is_x86_feature_detected!("avx512bw")
+                    && is_x86_feature_detected!("avx512vbmi")
+                    && is_x86_feature_detected!("avx512vbmi2")
+            }
+        }
+    }
+}
+
+/// Whether the tests of `backend` can run on this CPU. When they can't, the skip is
+/// written straight to stderr, since the test harness captures `eprintln!` and the
+/// test would quietly show as passing.
+#[cfg(test)]
+pub(crate) fn can_test(backend: Backend) -> bool {
+    use std::io::Write;
+
+    let supported = backend.is_supported();
+    if !supported {
+        let thread = std::thread::current();
+        let test = thread.name().unwrap_or("test");
+        let _ = writeln!(
+            std::io::stderr(),
+            "skipping {test}: this CPU doesn't support the {backend:?} backend"
+        );
+    }
+    supported
+}
+
+static BACKEND: OnceLock<Backend> = OnceLock::new();
+
+/// Picks the most preferred backend the current CPU supports.
+fn detect_backend() -> Backend {
+    Backend::ALL
+        .into_iter()
+        .rev()
+        .find(|backend| backend.is_supported())
+        .unwrap_or(Backend::Scalar)
+}
+
+/// The backend used by [`parse_ints`]. Feature detection only happens on the first call.
+pub fn backend() -> Backend {
+    *BACKEND.get_or_init(detect_backend)
+}
+
+/// Whether the 16 byte parser can run, which every backend but the scalar one implies.
+/// The SIMD entry points outside of this module check this before using SSSE3.
+pub(crate) fn has_ssse3() -> bool {
+    backend() != Backend::Scalar
+}
+
+/// Parses the integers out of `bytes` with the fastest backend this CPU supports.
+pub fn parse_ints(bytes: &[u8], lookup_table: &[PatternData]) -> Vec<u32> {
+    parse_ints_with(backend(), bytes, lookup_table)
+}
+
+/// Parses the integers out of `bytes` with a specific backend.
+///
+/// Panics if the CPU doesn't support `backend`.
+pub fn parse_ints_with(backend: Backend, bytes: &[u8], lookup_table: &[PatternData]) -> Vec<u32> {
+    assert!(
+        backend.is_supported(),
+        "{backend:?} backend isn't supported on this CPU"
+    );
+    match backend {
+        Backend::Scalar => scalar::parse_ints(bytes),
+        // SAFETY: We just checked that the CPU supports SSSE3.
+        Backend::Ssse3 => unsafe { parse_ints_ssse3(bytes, lookup_table) },
+        // SAFETY: We just checked that the CPU supports AVX-512 F, BW, VBMI, and VBMI2.
+        Backend::Avx512 => unsafe { parse_ints_avx512(bytes) },
+    }
+}
+
+/// Compiles the 16 byte parser with SSSE3 enabled so the intrinsics get inlined.
+#[target_feature(enable = "ssse3")]
+unsafe fn parse_ints_ssse3(bytes: &[u8], lookup_table: &[PatternData]) -> Vec<u32> {
+    simd::parse_ints(bytes, lookup_table)
+}
+
+/// Compiles the 64 byte parser with AVX-512 enabled so the intrinsics get inlined.
+#[target_feature(enable = "avx512f,avx512bw,avx512vbmi,avx512vbmi2")]
+unsafe fn parse_ints_avx512(bytes: &[u8]) -> Vec<u32> {
+    simd::avx512::parse_ints(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simd::LOOKUP_TABLE;
+
+    #[test]
+    fn selects_a_supported_backend() {
+        assert!(backend().is_supported());
+        assert_eq!(backend(), backend());
+    }
+
+    #[test]
+    fn scalar_is_always_supported() {
+        assert!(Backend::Scalar.is_supported());
+    }
+
+    #[test]
+    fn supported_backends_agree_on_day1_input() {
+        let input = include_bytes!("../../input/day1.txt");
+        let expected = parse_ints_with(Backend::Scalar, input, &LOOKUP_TABLE);
+        assert_eq!(expected.len(), 2000);
+        for backend in Backend::ALL.into_iter().filter(|b| b.is_supported()) {
+            let parsed = parse_ints_with(backend, input, &LOOKUP_TABLE);
+            assert_eq!(expected, parsed, "{backend:?} disagrees with scalar");
+        }
+    }
+
+    /// Writes a number of every length up to 24 digits at every offset of a 48 byte
+    /// buffer, on its own and between other numbers, so it lands on every possible
+    /// place relative to the windows of each backend.
+    #[test]
+    fn parses_a_number_at_every_offset() {
+        let digits = b"987654321098765432109876";
+        let backgrounds: [&[u8; 48]; 2] = [
+            &[b' '; 48],
+            b"1 22 333 4444 1 22 333 4444 1 22 333 4444 1 22 3",
+        ];
+        for len in 1..=digits.len() {
+            let number = &digits[..len];
+            let value = number.iter().fold(0u32, |n, d| {
+                n.wrapping_mul(10).wrapping_add(u32::from(d - b'0'))
+            });
+            for offset in 0..=48 - len {
+                for background in backgrounds {
+                    let mut input = *background;
+                    input[offset..offset + len].copy_from_slice(number);
+                    // Keep the neighbours from running into the number
+                    if offset > 0 {
+                        input[offset - 1] = b',';
+                    }
+                    if offset + len < 48 {
+                        input[offset + len] = b',';
+                    }
+                    let expected = scalar::parse_ints(&input);
+                    assert!(expected.contains(&value));
+                    for backend in Backend::ALL.into_iter().filter(|b| b.is_supported()) {
+                        let parsed = parse_ints_with(backend, &input, &LOOKUP_TABLE);
+                        assert_eq!(
+                            expected, parsed,
+                            "{backend:?} disagrees with scalar on {len} digits at {offset}"
+                        );
+                    }
+                    let parsed = crate::portable::parse_ints(&input, &LOOKUP_TABLE);
+                    assert_eq!(expected, parsed, "portable disagrees with scalar");
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,17 @@
-#![feature(vec_into_raw_parts)]
-#![feature(stdarch_x86_avx512)]
-#![feature(iter_array_chunks)]
 #![feature(portable_simd)]
+#[cfg(target_arch = "x86_64")]
+pub mod columns;
+#[cfg(target_arch = "x86_64")]
+pub mod dispatch;
+pub mod options;
+#[cfg(target_arch = "x86_64")]
+pub mod parallel;
+pub mod portable;
+#[cfg(target_arch = "x86_64")]
+pub mod rows;
 pub mod scalar;
+pub mod separators;
 #[cfg(target_arch = "x86_64")]
 pub mod simd;
+#[cfg(target_arch = "x86_64")]
+pub mod stream;
@@ -0,0 +1,112 @@
+//! Options for how picky the parsers are about their input.
+use std::{fmt, ops::Range};
+
+use crate::separators::{LineEnding, Separators};
+
+/// Controls which bytes the parsers accept between numbers.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// The bytes that can separate numbers when `strict` is set.
+    pub allowed_separators: Separators,
+    /// Whether to reject any byte that's neither a digit nor in `allowed_separators`.
+    /// Otherwise every non-digit byte separates numbers.
+    pub strict: bool,
+}
+
+impl ParseOptions {
+    /// Options that only accept digits and `allowed_separators`.
+    pub fn strict(allowed_separators: Separators) -> Self {
+        Self {
+            allowed_separators,
+            strict: true,
+        }
+    }
+
+    /// Checks every byte of `bytes` in `range`, looking one byte past it to tell a `\r\n`
+    /// apart from a lone `\r`.
+    pub(crate) fn check(&self, bytes: &[u8], range: Range<usize>) -> Result<(), ParseError> {
+        if !self.strict {
+            return Ok(());
+        }
+        match range.into_iter().find(|i| !self.allows(bytes, *i)) {
+            Some(offset) => Err(ParseError {
+                offset,
+                byte: bytes[offset],
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether `bytes[i]` is a digit or a separator. With [`LineEnding::CrLf`] a `\r` is
+    /// only allowed as the first half of a line ending.
+    fn allows(&self, bytes: &[u8], i: usize) -> bool {
+        let byte = bytes[i];
+        if byte == b'\r' && self.allowed_separators.line_ending() == LineEnding::CrLf {
+            return bytes.get(i + 1) == Some(&b'\n');
+        }
+        byte.is_ascii_digit() || self.allowed_separators.contains(byte)
+    }
+}
+
+/// The error returned in strict mode when the input has a byte that isn't allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    /// Where the first unexpected byte is in the input.
+    pub offset: usize,
+    /// The unexpected byte.
+    pub byte: u8,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unexpected byte {:#04x} ({:?}) at offset {}",
+            self.byte,
+            char::from(self.byte),
+            self.offset
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The error returned when a number doesn't fit in the type it's parsed into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntOverflow {
+    /// Where the first number that's too big starts in the input.
+    pub offset: usize,
+    /// The biggest number the type can hold.
+    pub max: u64,
+}
+
+impl fmt::Display for IntOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "number at offset {} is bigger than {}",
+            self.offset, self.max
+        )
+    }
+}
+
+impl std::error::Error for IntOverflow {}
+
+/// The error returned when the numbers in the input don't fit in the output slice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputTooSmall {
+    /// How many numbers were written to the front of the slice before it filled up.
+    pub n_written: usize,
+}
+
+impl fmt::Display for OutputTooSmall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "output slice filled up after {} numbers with more left to parse",
+            self.n_written
+        )
+    }
+}
+
+impl std::error::Error for OutputTooSmall {}
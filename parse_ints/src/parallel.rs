@@ -0,0 +1,97 @@
+//! Splitting big inputs across threads.
+use std::{ops::Range, thread};
+
+use crate::{dispatch, simd::LOOKUP_TABLE};
+
+/// Parses the integers out of `bytes` on `n_threads` threads, giving the same result
+/// as [`dispatch::parse_ints`].
+///
+/// The input is cut into roughly equal chunks, each moved forward to the end of any
+/// digit run it lands in so no number is split. Each chunk is parsed with the fastest
+/// backend this CPU supports and the results are concatenated in order. Zero threads
+/// is treated as one.
+pub fn par_parse_ints(bytes: &[u8], n_threads: usize) -> Vec<u32> {
+    if n_threads <= 1 {
+        return dispatch::parse_ints(bytes, &LOOKUP_TABLE);
+    }
+    let chunks = split_at_separators(bytes, n_threads);
+    let parsed: Vec<Vec<u32>> = thread::scope(|scope| {
+        let handles: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| scope.spawn(|| dispatch::parse_ints(&bytes[chunk], &LOOKUP_TABLE)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+    let mut output = Vec::with_capacity(parsed.iter().map(Vec::len).sum());
+    for numbers in parsed {
+        output.extend_from_slice(&numbers);
+    }
+    output
+}
+
+/// Cuts `bytes` into at most `n_chunks` ranges that cover all of it, where every
+/// range but the last ends on a separator. Chunks swallowed by a long digit run are
+/// dropped rather than left empty.
+fn split_at_separators(bytes: &[u8], n_chunks: usize) -> Vec<Range<usize>> {
+    let mut chunks = Vec::with_capacity(n_chunks);
+    let mut start = 0;
+    for i in 1..n_chunks {
+        let mut end = (bytes.len() * i / n_chunks).max(start);
+        while end < bytes.len() && bytes[end].is_ascii_digit() {
+            end += 1;
+        }
+        if end > start {
+            chunks.push(start..end);
+            start = end;
+        }
+    }
+    if start < bytes.len() || chunks.is_empty() {
+        chunks.push(start..bytes.len());
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn matches_serial_on_puzzle_inputs() {
+        for input in [
+            &include_bytes!("../../input/day1.txt")[..],
+            &include_bytes!("../../input/day2.txt")[..],
+        ] {
+            let expected = dispatch::parse_ints(input, &LOOKUP_TABLE);
+            for n_threads in 0..=8 {
+                assert_eq!(par_parse_ints(input, n_threads), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn never_splits_a_number() {
+        assert_eq!(split_at_separators(b"123456789", 4), vec![0..9]);
+        assert_eq!(
+            split_at_separators(b"12 34 56 78", 4),
+            vec![0..2, 2..5, 5..8, 8..11]
+        );
+        assert_eq!(split_at_separators(b"", 3), vec![0..0]);
+    }
+
+    proptest! {
+        #[test]
+        fn matches_serial(
+            nums in prop::collection::vec(0u32..=99_999_999, 0..200),
+            seps in "[ \n,]{1,3}",
+            n_threads in 1usize..16,
+        ) {
+            let input = nums.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(&seps);
+            let expected = dispatch::parse_ints(input.as_bytes(), &LOOKUP_TABLE);
+            prop_assert_eq!(par_parse_ints(input.as_bytes(), n_threads), expected);
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! A 16 byte parser written only with `core::simd`, so it builds for any target.
+//!
+//! It walks the input the same way as [`crate::simd::parse_ints`] and uses the same
+//! lookup table, with a swizzle in place of `pshufb` and lane-wise multiply-adds in
+//! place of `pmaddubsw` and `pmaddwd`. Off x86 it's the reference for the other
+//! backends, on x86 it's tested against the intrinsics.
+use std::simd::{cmp::SimdPartialOrd, num::SimdUint, simd_swizzle, u16x8, u32x4, u8x16};
+
+#[cfg(target_arch = "x86_64")]
+pub use crate::simd::{PatternData, LOOKUP_TABLE};
+
+// The intrinsic backend owns the table on x86, everywhere else it's built here from
+// the same generated source.
+#[cfg(not(target_arch = "x86_64"))]
+#[allow(dead_code)]
+#[path = "simd/pattern.rs"]
+mod pattern;
+
+#[cfg(not(target_arch = "x86_64"))]
+pub use pattern::{PatternData, LOOKUP_TABLE_LEN};
+
+/// The lookup table for every 16 bit pattern, generated by the build script.
+#[cfg(not(target_arch = "x86_64"))]
+pub static LOOKUP_TABLE: [PatternData; LOOKUP_TABLE_LEN] =
+    include!(concat!(env!("OUT_DIR"), "/lookup_table.rs"));
+
+/// Parses the integers out of `bytes` 16 bytes at a time.
+pub fn parse_ints(bytes: &[u8], lookup_table: &[PatternData]) -> Vec<u32> {
+    let mut output = Vec::with_capacity(bytes.len() / 2 + 16);
+    parse_ints_into(bytes, lookup_table, &mut output);
+    output
+}
+
+/// Parses the integers out of `bytes` 16 bytes at a time, appending them to `output`.
+pub fn parse_ints_into(bytes: &[u8], lookup_table: &[PatternData], output: &mut Vec<u32>) {
+    let mut input_cursor = 0;
+    while let Some(chunk) = next_chunk(bytes, lookup_table, &mut input_cursor) {
+        match chunk {
+            Chunk::Window(input, pattern_data) => convert_window(input, pattern_data, output),
+            Chunk::LongRun(digits) => output.push(convert_slow(digits)),
+        }
+    }
+}
+
+/// Where the digits are in `input`, with the first byte in the highest bit.
+#[inline(always)]
+fn digit_bitmask(input: u8x16) -> u16 {
+    let is_digit = input.simd_ge(u8x16::splat(b'0')) & input.simd_le(u8x16::splat(b'9'));
+    (is_digit.to_bitmask() as u16).reverse_bits()
+}
+
+/// The next piece of the input that has numbers in it.
+enum Chunk<'a, 'b> {
+    /// A window and its lookup table entry.
+    Window(u8x16, &'a PatternData),
+    /// The digits of a number too long to fit in a window.
+    LongRun(&'b [u8]),
+}
+
+/// Finds the next chunk at or after `input_cursor` that has complete numbers in it,
+/// and moves `input_cursor` past the numbers it contains. The windows at the end are
+/// padded with spaces.
+///
+/// A window without complete numbers moves `input_cursor` to the start of the run of
+/// digits at its end, or past it when the run is the whole window.
+#[inline(always)]
+fn next_chunk<'a, 'b>(
+    bytes: &'b [u8],
+    lookup_table: &'a [PatternData],
+    input_cursor: &mut usize,
+) -> Option<Chunk<'a, 'b>> {
+    let n_bytes = bytes.len();
+    while *input_cursor < n_bytes {
+        let start = *input_cursor;
+        let input = if start + 16 < n_bytes {
+            u8x16::from_slice(&bytes[start..])
+        } else {
+            let mut extra = [b' '; 16];
+            extra[..n_bytes - start].copy_from_slice(&bytes[start..]);
+            u8x16::from_array(extra)
+        };
+        let digit_bitmask = digit_bitmask(input);
+        let pattern_data = &lookup_table[usize::from(digit_bitmask)];
+        if pattern_data.n_extracted > 0 {
+            *input_cursor += usize::from(pattern_data.skip);
+            return Some(Chunk::Window(input, pattern_data));
+        }
+        let run_offset = 16 - digit_bitmask.trailing_ones() as usize;
+        if run_offset > 0 {
+            *input_cursor += run_offset;
+            continue;
+        }
+        let len = bytes[start..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        *input_cursor += len;
+        return Some(Chunk::LongRun(&bytes[start..start + len]));
+    }
+    None
+}
+
+/// Converts a run of digits one at a time, wrapping the same way as the windows.
+#[cold]
+fn convert_slow(digits: &[u8]) -> u32 {
+    digits.iter().fold(0u32, |acc, byte| {
+        acc.wrapping_mul(10).wrapping_add(u32::from(byte - b'0'))
+    })
+}
+
+/// Shuffles the complete numbers in a window into place and converts them, appending
+/// them to `output`.
+#[inline(always)]
+fn convert_window(input: u8x16, pattern_data: &PatternData, output: &mut Vec<u32>) {
+    // Like `pshufb`, out of range indices (the 0x80s) give a zero
+    let shuffled = input.swizzle_dyn(u8x16::from_array(pattern_data.shuffle_array));
+    let digits = shuffled.saturating_sub(u8x16::splat(b'0'));
+    let n_extracted = usize::from(pattern_data.n_extracted);
+    match pattern_data.conversion_size {
+        0 => {}
+        1 => output.extend_from_slice(&digits.cast::<u32>().to_array()[..n_extracted]),
+        2 => {
+            let converted = two_digits(digits).cast::<u32>();
+            output.extend_from_slice(&converted.to_array()[..n_extracted]);
+        }
+        4 => {
+            let converted = four_digits(two_digits(digits));
+            output.extend_from_slice(&converted.to_array()[..n_extracted]);
+        }
+        8 => {
+            let converted = eight_digits(four_digits(two_digits(digits)));
+            output.extend_from_slice(&converted[..n_extracted]);
+        }
+        16 => {
+            let [high, low] = eight_digits(four_digits(two_digits(digits)));
+            let converted = u64::from(high) * 100_000_000 + u64::from(low);
+            // Wraps the same way as the intrinsic backend
+            output.push(converted as u32);
+        }
+        _ => panic!("invalid conversion size: {}", pattern_data.conversion_size),
+    }
+}
+
+/// Combines neighbouring digits into 2 digit numbers.
+#[inline(always)]
+fn two_digits(digits: u8x16) -> u16x8 {
+    let tens = simd_swizzle!(digits, [0, 2, 4, 6, 8, 10, 12, 14]).cast::<u16>();
+    let ones = simd_swizzle!(digits, [1, 3, 5, 7, 9, 11, 13, 15]).cast::<u16>();
+    tens * u16x8::splat(10) + ones
+}
+
+/// Combines neighbouring 2 digit numbers into 4 digit numbers.
+#[inline(always)]
+fn four_digits(two_digits: u16x8) -> u32x4 {
+    let hundreds = simd_swizzle!(two_digits, [0, 2, 4, 6]).cast::<u32>();
+    let ones = simd_swizzle!(two_digits, [1, 3, 5, 7]).cast::<u32>();
+    hundreds * u32x4::splat(100) + ones
+}
+
+/// Combines neighbouring 4 digit numbers into 8 digit numbers.
+#[inline(always)]
+fn eight_digits(four_digits: u32x4) -> [u32; 2] {
+    let [a, b, c, d] = four_digits.to_array();
+    [a * 10_000 + b, c * 10_000 + d]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn parses_puzzle_inputs() {
+        let input = include_bytes!("../../input/day1.txt");
+        let numbers = parse_ints(input, &LOOKUP_TABLE);
+        assert_eq!(numbers.len(), 2000);
+        assert_eq!(&numbers[..2], &[13432, 99527]);
+    }
+
+    proptest! {
+        #[test]
+        fn matches_str_parse(nums in prop::collection::vec(0u32..=99_999_999, 0..100), seps in "[ \n,]{1,3}") {
+            let mut input = nums.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(&seps);
+            input.push('\n');
+            prop_assert_eq!(parse_ints(input.as_bytes(), &LOOKUP_TABLE), nums);
+        }
+
+        #[cfg(target_arch = "x86_64")]
+        #[test]
+        fn matches_intrinsics(input in "[0-9 \n,]{0,200}") {
+            let expected = crate::simd::parse_ints(input.as_bytes(), &LOOKUP_TABLE);
+            prop_assert_eq!(parse_ints(input.as_bytes(), &LOOKUP_TABLE), expected);
+        }
+
+        #[cfg(target_arch = "x86_64")]
+        #[test]
+        fn matches_intrinsics_on_any_bytes(input in prop::collection::vec(any::<u8>(), 0..200)) {
+            let expected = crate::simd::parse_ints(&input, &LOOKUP_TABLE);
+            prop_assert_eq!(parse_ints(&input, &LOOKUP_TABLE), expected);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! Parsing lines of numbers into ragged rows.
+use std::{arch::x86_64::*, mem::MaybeUninit, ops::Range};
+
+use crate::{
+    dispatch::{self, Backend},
+    scalar,
+    simd::{
+        convert_window_into, first_bytes_mask, next_chunk, vector_to_bitmask, Chunk, PatternData,
+        LOOKUP_TABLE,
+    },
+};
+
+/// Rows of numbers with different lengths, stored back to back in one buffer.
+///
+/// Row `i` is `values[row_starts[i]..row_starts[i + 1]]`, so `row_starts` has one more
+/// entry than there are rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaggedRows {
+    /// Every number from every row, in order.
+    pub values: Vec<u32>,
+    /// Where each row starts in `values`, followed by `values.len()`.
+    pub row_starts: Vec<usize>,
+}
+
+impl RaggedRows {
+    /// How many rows there are.
+    pub fn len(&self) -> usize {
+        self.row_starts.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The numbers on row `i`.
+    pub fn row(&self, i: usize) -> &[u32] {
+        &self.values[self.row_starts[i]..self.row_starts[i + 1]]
+    }
+
+    /// Iterates over the rows in order.
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &[u32]> + '_ {
+        self.row_starts
+            .windows(2)
+            .map(|bounds| &self.values[bounds[0]..bounds[1]])
+    }
+}
+
+/// Builds up a [`RaggedRows`] one number and one newline at a time.
+struct RowsBuilder {
+    values: Vec<u32>,
+    row_starts: Vec<usize>,
+}
+
+impl RowsBuilder {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            values: Vec::with_capacity(capacity),
+            row_starts: vec![0],
+        }
+    }
+
+    /// Ends the current row for each of `n_newlines`.
+    #[inline(always)]
+    fn push_newlines(&mut self, n_newlines: u32) {
+        for _ in 0..n_newlines {
+            self.row_starts.push(self.values.len());
+        }
+    }
+
+    /// Ends the current row for each newline in `range` of `bytes`.
+    fn push_newlines_in(&mut self, bytes: &[u8], range: Range<usize>) {
+        let n_newlines = bytes[range].iter().filter(|b| **b == b'\n').count();
+        self.push_newlines(n_newlines as u32);
+    }
+
+    /// A newline at the very end of the input finishes the last row rather than
+    /// starting an empty one, the same as [`str::lines`].
+    fn finish(mut self, bytes: &[u8]) -> RaggedRows {
+        if bytes.last().is_none_or(|b| *b == b'\n') {
+            self.row_starts.pop();
+        }
+        self.row_starts.push(self.values.len());
+        RaggedRows {
+            values: self.values,
+            row_starts: self.row_starts,
+        }
+    }
+}
+
+/// Parses the integers out of `bytes`, starting a new row at every `\n`.
+///
+/// Blank lines are empty rows, and a trailing newline doesn't add an empty row at the
+/// end. Any other non-digit byte is a separator.
+pub fn parse_rows(bytes: &[u8]) -> RaggedRows {
+    match dispatch::backend() {
+        Backend::Scalar => parse_rows_scalar(bytes),
+        // SAFETY: The dispatcher only picks backends the CPU supports, and AVX-512
+        // implies SSSE3.
+        Backend::Ssse3 | Backend::Avx512 => unsafe { parse_rows_ssse3(bytes, &LOOKUP_TABLE) },
+    }
+}
+
+/// Parses each line with the scalar parser.
+fn parse_rows_scalar(bytes: &[u8]) -> RaggedRows {
+    let mut rows = RowsBuilder::with_capacity(bytes.len() / 2);
+    let mut lines = bytes.split(|b| *b == b'\n').peekable();
+    while let Some(line) = lines.next() {
+        rows.values.extend(scalar::parse_ints(line));
+        if lines.peek().is_some() {
+            rows.push_newlines(1);
+        }
+    }
+    rows.finish(bytes)
+}
+
+/// Parses with the 16 byte parser, finding the newlines in each window along with the
+/// digits. The newlines in the part of the window that was consumed are placed between
+/// the numbers by comparing their positions to where each number starts.
+#[target_feature(enable = "ssse3")]
+unsafe fn parse_rows_ssse3(bytes: &[u8], lookup_table: &[PatternData]) -> RaggedRows {
+    let mut rows = RowsBuilder::with_capacity(bytes.len() / 2);
+    let mut converted = [MaybeUninit::uninit(); 16];
+    let mut input_cursor = 0;
+    // Everything before this has had its newlines counted
+    let mut scanned_to = 0;
+    while let Some(chunk) = next_chunk(bytes, lookup_table, &mut input_cursor) {
+        // Windows without any complete numbers are skipped entirely
+        rows.push_newlines_in(bytes, scanned_to..chunk.start());
+        scanned_to = chunk.end(bytes.len());
+        let window = match chunk {
+            Chunk::Window(window) => window,
+            // A long run is a single number with no newlines in it
+            Chunk::LongRun(run) => {
+                rows.values.push(run.value(bytes, 10).0 as u32);
+                continue;
+            }
+        };
+        let n_consumed = scanned_to - window.start;
+
+        let newline_vector_mask = _mm_cmpeq_epi8(window.input, _mm_set1_epi8(b'\n' as i8));
+        let mut newlines = vector_to_bitmask(newline_vector_mask) & first_bytes_mask(n_consumed);
+        let mut starts = window.digit_bitmask & !(window.digit_bitmask >> 1);
+
+        let n_extracted = convert_window_into(window.input, window.pattern_data, &mut converted);
+        for n in &converted[..n_extracted] {
+            let start_bit = 0x8000 >> starts.leading_zeros();
+            // Earlier bytes are in higher bits
+            let newlines_before = newlines & !(start_bit | (start_bit - 1));
+            rows.push_newlines(newlines_before.count_ones());
+            newlines &= !newlines_before;
+            starts &= !start_bit;
+            // SAFETY: The first `n_extracted` numbers were written by the conversion.
+            rows.values.push(unsafe { n.assume_init() });
+        }
+        rows.push_newlines(newlines.count_ones());
+    }
+    rows.push_newlines_in(bytes, scanned_to..bytes.len());
+    rows.finish(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn to_vecs(rows: &RaggedRows) -> Vec<Vec<u32>> {
+        rows.rows().map(|row| row.to_vec()).collect()
+    }
+
+    #[test]
+    fn splits_rows_on_newlines() {
+        let rows = parse_rows(b"1 2 3\n\n45 6\n7");
+        assert_eq!(
+            to_vecs(&rows),
+            vec![vec![1, 2, 3], vec![], vec![45, 6], vec![7]]
+        );
+        assert_eq!(rows.row(2), &[45, 6]);
+        assert_eq!(rows.len(), 4);
+    }
+
+    #[test]
+    fn trailing_newline_does_not_add_a_row() {
+        assert_eq!(to_vecs(&parse_rows(b"1 2\n3\n")), vec![vec![1, 2], vec![3]]);
+        assert_eq!(to_vecs(&parse_rows(b"\n")), vec![Vec::<u32>::new()]);
+        assert!(parse_rows(b"").is_empty());
+    }
+
+    #[test]
+    fn matches_scalar_on_puzzle_input() {
+        let input = include_bytes!("../../input/day2.txt");
+        let rows = parse_rows(input);
+        assert_eq!(rows, parse_rows_scalar(input));
+        assert_eq!(rows.len(), 1000);
+    }
+
+    proptest! {
+        #[test]
+        fn matches_lines(
+            lines in prop::collection::vec(prop::collection::vec(0u32..=99999, 0..10), 0..50),
+            trailing_newline in any::<bool>(),
+        ) {
+            let mut input = lines
+                .iter()
+                .map(|line| line.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(" "))
+                .collect::<Vec<_>>()
+                .join("\n");
+            if trailing_newline {
+                input.push('\n');
+            }
+            let expected: Vec<Vec<u32>> = input
+                .lines()
+                .map(|line| line.split(' ').filter_map(|n| n.parse().ok()).collect())
+                .collect();
+            prop_assert_eq!(to_vecs(&parse_rows(input.as_bytes())), expected.clone());
+            prop_assert_eq!(to_vecs(&parse_rows_scalar(input.as_bytes())), expected);
+        }
+    }
+}
@@ -1,46 +1,440 @@
-use std::{
-    arch::x86_64::{_mm_madd_epi16, _mm_maddubs_epi16, _mm_packus_epi32},
-    simd::{u16x8, u8x16},
+//! The one-byte-at-a-time parser. It uses no intrinsics or vector types, so it builds
+//! for any target, is the reference the other backends are checked against, and is
+//! what the SIMD entry points fall back to on CPUs without SSSE3.
+//!
+//! It mirrors the entry points of [`crate::simd`] without the lookup table.
+use crate::{
+    options::{IntOverflow, OutputTooSmall, ParseError, ParseOptions},
+    separators::LineEnding,
 };
 
-use crate::simd::{print_vec_u16, print_vec_u32, print_vec_u8};
+/// Parses every run of ASCII digits in `bytes`, treating any other byte as a
+/// separator. This is the fallback for CPUs that can't run the SIMD parser.
+///
+/// Numbers too big for a `u32` wrap, the same as [`crate::simd::parse_ints`].
+pub fn parse_ints(bytes: &[u8]) -> Vec<u32> {
+    let mut output = Vec::new();
+    parse_ints_into(bytes, &mut output);
+    output
+}
+
+/// Parses the same numbers as [`parse_ints`], appending them to `output`.
+pub fn parse_ints_into(bytes: &[u8], output: &mut Vec<u32>) {
+    let mut current: Option<u32> = None;
+    for byte in bytes.iter() {
+        if byte.is_ascii_digit() {
+            let digit = u32::from(*byte - b'0');
+            let so_far = current.unwrap_or(0);
+            current = Some(so_far.wrapping_mul(10).wrapping_add(digit));
+        } else if let Some(num) = current.take() {
+            output.push(num);
+        }
+    }
+    if let Some(num) = current {
+        output.push(num);
+    }
+}
+
+/// Parses the same numbers as [`parse_ints`] into `output`, returning how many were
+/// written. When they don't all fit, `output` is filled before returning the error.
+pub fn parse_ints_to_slice(bytes: &[u8], output: &mut [u32]) -> Result<usize, OutputTooSmall> {
+    let mut n_written = 0;
+    for n in IntIter::new(bytes) {
+        let Some(dest) = output.get_mut(n_written) else {
+            return Err(OutputTooSmall { n_written });
+        };
+        *dest = n;
+        n_written += 1;
+    }
+    Ok(n_written)
+}
+
+/// An iterator over the same numbers as [`parse_ints`], parsing each one as it's
+/// asked for.
+pub struct IntIter<'a> {
+    bytes: &'a [u8],
+    input_cursor: usize,
+}
+
+impl<'a> IntIter<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            input_cursor: 0,
+        }
+    }
+}
+
+impl Iterator for IntIter<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        let rest = &self.bytes[self.input_cursor..];
+        let start = rest.iter().position(u8::is_ascii_digit)?;
+        let len = rest[start..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        self.input_cursor += start + len;
+        Some(rest[start..start + len].iter().fold(0u32, |n, d| {
+            n.wrapping_mul(10).wrapping_add(u32::from(d - b'0'))
+        }))
+    }
+}
+
+/// Parses the same numbers as [`parse_ints`] as `u64`s, which wrap instead.
+pub fn parse_ints_u64(bytes: &[u8]) -> Vec<u64> {
+    digit_runs(bytes)
+        .map(|(_, run)| wrapping_value(run))
+        .collect()
+}
+
+/// The unsigned integer types the `parse_ints_as` functions can parse into.
+pub trait UnsignedTarget: Copy {
+    /// The biggest value that fits.
+    const MAX: u64;
+
+    /// The low bits of `value`.
+    fn from_u64_wrapping(value: u64) -> Self;
+}
+
+impl UnsignedTarget for u8 {
+    const MAX: u64 = u8::MAX as u64;
+
+    #[inline(always)]
+    fn from_u64_wrapping(value: u64) -> Self {
+        value as Self
+    }
+}
+
+impl UnsignedTarget for u16 {
+    const MAX: u64 = u16::MAX as u64;
+
+    #[inline(always)]
+    fn from_u64_wrapping(value: u64) -> Self {
+        value as Self
+    }
+}
+
+impl UnsignedTarget for u32 {
+    const MAX: u64 = u32::MAX as u64;
+
+    #[inline(always)]
+    fn from_u64_wrapping(value: u64) -> Self {
+        value as Self
+    }
+}
+
+impl UnsignedTarget for u64 {
+    const MAX: u64 = u64::MAX;
+
+    #[inline(always)]
+    fn from_u64_wrapping(value: u64) -> Self {
+        value
+    }
+}
+
+/// Parses the integers out of `bytes` as `T`, failing on the first number that
+/// doesn't fit, like [`crate::simd::parse_ints_as`].
+pub fn parse_ints_as<T: UnsignedTarget>(bytes: &[u8]) -> Result<Vec<T>, IntOverflow> {
+    parse_radix(bytes, 10)
+}
+
+/// Parses the hexadecimal integers out of `bytes` as `T`, like
+/// [`crate::simd::parse_hex_ints`]. Both `a-f` and `A-F` are digits.
+pub fn parse_hex_ints<T: UnsignedTarget>(bytes: &[u8]) -> Result<Vec<T>, IntOverflow> {
+    parse_radix(bytes, 16)
+}
+
+/// Parses the binary integers out of `bytes` as `T`, like
+/// [`crate::simd::parse_bin_ints`].
+pub fn parse_bin_ints<T: UnsignedTarget>(bytes: &[u8]) -> Result<Vec<T>, IntOverflow> {
+    parse_radix(bytes, 2)
+}
+
+/// Parses every run of digits in `radix` as `T`, failing on the first one that
+/// doesn't fit.
+fn parse_radix<T: UnsignedTarget>(bytes: &[u8], radix: u32) -> Result<Vec<T>, IntOverflow> {
+    radix_runs(bytes, radix)
+        .map(|(start, run)| {
+            let value = run.iter().try_fold(0u64, |n, d| {
+                let digit = char::from(*d).to_digit(radix)?;
+                n.checked_mul(radix.into())?.checked_add(digit.into())
+            });
+            match value {
+                Some(value) if value <= T::MAX => Ok(T::from_u64_wrapping(value)),
+                _ => Err(IntOverflow {
+                    offset: start,
+                    max: T::MAX,
+                }),
+            }
+        })
+        .collect()
+}
+
+/// The signed integer types the `parse_ints_signed` functions can parse into.
+pub trait SignedTarget: Copy {
+    /// The low bits of `value`, which is all of it for `i64`.
+    fn from_i64_wrapping(value: i64) -> Self;
+}
+
+impl SignedTarget for i32 {
+    fn from_i64_wrapping(value: i64) -> Self {
+        value as i32
+    }
+}
+
+impl SignedTarget for i64 {
+    fn from_i64_wrapping(value: i64) -> Self {
+        value
+    }
+}
+
+/// Parses the integers out of `bytes`, where a `-` directly before the first digit
+/// makes a number negative, like [`crate::simd::parse_ints_signed`]. The numbers are
+/// signed as `i64`s and narrowed to `T`, wrapping when they don't fit.
+pub fn parse_ints_signed<T: SignedTarget>(bytes: &[u8]) -> Vec<T> {
+    digit_runs(bytes)
+        .map(|(start, run)| {
+            let negative = start > 0 && bytes[start - 1] == b'-';
+            T::from_i64_wrapping(signed(wrapping_value(run), negative))
+        })
+        .collect()
+}
+
+/// `magnitude` as an `i64` with its sign, wrapping when it doesn't fit.
+#[inline(always)]
+pub(crate) fn signed(magnitude: u64, negative: bool) -> i64 {
+    if negative {
+        (magnitude as i64).wrapping_neg()
+    } else {
+        magnitude as i64
+    }
+}
+
+/// The value of a run of decimal digits, wrapping around past `u64::MAX`.
+fn wrapping_value(run: &[u8]) -> u64 {
+    run.iter().fold(0u64, |n, d| {
+        n.wrapping_mul(10).wrapping_add(u64::from(d - b'0'))
+    })
+}
+
+/// A fixed-point number worth `value / 10^scale`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decimal {
+    pub value: i64,
+    /// How many digits there were after the `.`.
+    pub scale: u32,
+}
+
+impl Decimal {
+    /// The closest `f64`, give or take the rounding of the division.
+    pub fn to_f64(self) -> f64 {
+        self.value as f64 / 10f64.powi(self.scale as i32)
+    }
+}
+
+impl From<Decimal> for f64 {
+    fn from(decimal: Decimal) -> f64 {
+        decimal.to_f64()
+    }
+}
+
+/// Parses the decimal numbers out of `bytes`, like [`crate::simd::parse_decimals`].
+///
+/// A `.` with digits on both sides joins them into one number, and a `-` directly
+/// before the first digit makes it negative. Numbers with more than 18 digits in total
+/// wrap.
+pub fn parse_decimals(bytes: &[u8]) -> Vec<Decimal> {
+    let mut output: Vec<Decimal> = Vec::new();
+    // The end, sign and magnitude of the last number while it could still get a
+    // fractional part
+    let mut open: Option<(usize, bool, u64)> = None;
+    for (start, run) in digit_runs(bytes) {
+        let len = run.len() as u32;
+        let magnitude = wrapping_value(run);
+        if let Some((_, negative, integer)) =
+            open.take_if(|(end, ..)| *end + 1 == start && bytes[*end] == b'.')
+        {
+            let magnitude = integer
+                .wrapping_mul(10u64.wrapping_pow(len))
+                .wrapping_add(magnitude);
+            *output.last_mut().unwrap() = Decimal {
+                value: signed(magnitude, negative),
+                scale: len,
+            };
+            continue;
+        }
+        let negative = start > 0 && bytes[start - 1] == b'-';
+        output.push(Decimal {
+            value: signed(magnitude, negative),
+            scale: 0,
+        });
+        open = Some((start + run.len(), negative, magnitude));
+    }
+    output
+}
+
+/// Each run of ASCII digits in `bytes` along with its offset.
+fn digit_runs(bytes: &[u8]) -> impl Iterator<Item = (usize, &[u8])> {
+    radix_runs(bytes, 10)
+}
+
+/// Each run of digits in `radix` in `bytes` along with its offset.
+fn radix_runs(bytes: &[u8], radix: u32) -> impl Iterator<Item = (usize, &[u8])> {
+    let mut start = 0;
+    let is_digit = move |b: &u8| char::from(*b).is_digit(radix);
+    bytes.split(move |b| !is_digit(b)).filter_map(move |run| {
+        let run_start = start;
+        start += run.len() + 1;
+        (!run.is_empty()).then_some((run_start, run))
+    })
+}
+
+/// Parses the integers out of `bytes`, first checking every byte against `options`.
+pub fn parse_ints_with_options(
+    bytes: &[u8],
+    options: &ParseOptions,
+) -> Result<Vec<u32>, ParseError> {
+    options.check(bytes, 0..bytes.len())?;
+    Ok(parse_ints(bytes))
+}
 
-const TWO_DIGITS: u8x16 =
-    u8x16::from_array([10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1]);
-const FOUR_DIGITS: u16x8 = u16x8::from_array([100, 1, 100, 1, 100, 1, 100, 1]);
-// const EIGHT_DIGITS: u32x4 = u32x4::from_array([10000, 1, 10000, 1]);
-// const SIXTEEN_DIGITS: u64x2 = u64x2::from_array([100000000, 1]);
+/// A parsed number along with where it came from in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub value: u32,
+    /// The offset of the first digit in the input.
+    pub start: usize,
+    /// How many digits the number has.
+    pub len: usize,
+}
+
+/// Parses the same numbers as [`parse_ints`], also recording where each one is.
+pub fn parse_ints_with_spans(bytes: &[u8]) -> Vec<Span> {
+    let mut output = Vec::new();
+    let mut current: Option<Span> = None;
+    for (i, byte) in bytes.iter().enumerate() {
+        if byte.is_ascii_digit() {
+            let digit = u32::from(*byte - b'0');
+            let span = current.get_or_insert(Span {
+                value: 0,
+                start: i,
+                len: 0,
+            });
+            span.value = span.value.wrapping_mul(10).wrapping_add(digit);
+            span.len += 1;
+        } else if let Some(span) = current.take() {
+            output.push(span);
+        }
+    }
+    if let Some(span) = current {
+        output.push(span);
+    }
+    output
+}
 
+/// Parses the numbers on the first line of `input_raw`. Lines end with `\n`, so this
+/// works for both `\n` and `\r\n` line endings.
+///
+/// Numbers too big for a `u32` wrap, the same as [`parse_ints`].
 pub fn parse_until_newline(input_raw: &[u8]) -> Vec<u32> {
+    parse_first_line(input_raw, LineEnding::Lf)
+}
+
+/// Parses the numbers on the first line of `input_raw`, where lines end with the line
+/// ending of `options.allowed_separators`, first checking that line against `options`.
+///
+/// With [`LineEnding::CrLf`] a `\n` on its own doesn't end the line.
+pub fn parse_until_newline_with(
+    input_raw: &[u8],
+    options: &ParseOptions,
+) -> Result<Vec<u32>, ParseError> {
+    let line_ending = options.allowed_separators.line_ending();
+    let line_len = (0..input_raw.len())
+        .find(|i| ends_line(input_raw, *i, line_ending))
+        .unwrap_or(input_raw.len());
+    options.check(input_raw, 0..line_len)?;
+    Ok(parse_first_line(input_raw, line_ending))
+}
+
+fn parse_first_line(input_raw: &[u8], line_ending: LineEnding) -> Vec<u32> {
     let mut output = Vec::new();
     let mut input = input_raw;
     let mut cursor = 0;
-    while let Some(found) = extract_digits(input) {
+    while let Some(found) = extract_digits(input, line_ending) {
+        let num = if found.n_digits <= 16 {
+            convert_digits(&found.digits) as u32
+        } else {
+            let run = &input[found.start..found.start + found.n_digits];
+            run.iter().fold(0u32, |n, d| {
+                n.wrapping_mul(10).wrapping_add(u32::from(d - b'0'))
+            })
+        };
         cursor += found.consumed;
         input = &input_raw[cursor..];
-        let num = convert_digits(&found.digits);
         output.push(num);
     }
     output
 }
 
+/// Parses the numbers on the first line of `input_raw` as `u64`s, failing on the
+/// first one that doesn't fit.
+pub fn parse_until_newline_u64(input_raw: &[u8]) -> Result<Vec<u64>, IntOverflow> {
+    let mut output = Vec::new();
+    let mut input = input_raw;
+    let mut cursor = 0;
+    while let Some(found) = extract_digits(input, LineEnding::Lf) {
+        let num = if found.n_digits <= 16 {
+            convert_digits(&found.digits)
+        } else {
+            // Up to 20 digits can still fit, one at a time is fine for those
+            let run = &input[found.start..found.start + found.n_digits];
+            let checked = run.iter().try_fold(0u64, |n, d| {
+                n.checked_mul(10)?.checked_add(u64::from(d - b'0'))
+            });
+            checked.ok_or(IntOverflow {
+                offset: cursor + found.start,
+                max: u64::MAX,
+            })?
+        };
+        cursor += found.consumed;
+        input = &input_raw[cursor..];
+        output.push(num);
+    }
+    Ok(output)
+}
+
 #[derive(Debug)]
 struct FoundNumber {
     consumed: usize,
-    _n_digits: usize,
+    /// Where the first digit is.
+    start: usize,
+    n_digits: usize,
+    /// The digit values right-aligned, if there are at most 16 of them.
     digits: [u8; 16],
 }
 
-fn extract_digits(input: &[u8]) -> Option<FoundNumber> {
+/// Whether `line_ending` starts at `input[i]`.
+fn ends_line(input: &[u8], i: usize, line_ending: LineEnding) -> bool {
+    match line_ending {
+        LineEnding::Lf => input[i] == b'\n',
+        LineEnding::CrLf => input[i] == b'\r' && input.get(i + 1) == Some(&b'\n'),
+    }
+}
+
+fn extract_digits(input: &[u8], line_ending: LineEnding) -> Option<FoundNumber> {
     let mut cursor = 0;
+    let mut start = 0;
     let mut n_digits = 0;
-    let newline = 13;
     let mut digits_array: [u8; 16] = [0; 16];
     for byte in input.iter() {
         cursor += 1;
         // End of input we're concerned about
-        if *byte == newline {
+        if ends_line(input, cursor - 1, line_ending) {
             if n_digits > 0 {
+                // Leave the newline for the next call so that it stops there
+                cursor -= 1;
                 break;
             } else {
                 return None;
@@ -48,7 +442,13 @@ fn extract_digits(input: &[u8]) -> Option<FoundNumber> {
         }
         // Found a digit byte
         if (*byte <= b'9') && (*byte >= b'0') {
-            digits_array[n_digits] = *byte - b'0';
+            if n_digits == 0 {
+                start = cursor - 1;
+            }
+            // Longer numbers are converted from the input instead
+            if n_digits < 16 {
+                digits_array[n_digits] = *byte - b'0';
+            }
             n_digits += 1;
             continue;
         }
@@ -58,10 +458,11 @@ fn extract_digits(input: &[u8]) -> Option<FoundNumber> {
         }
     }
     if n_digits > 0 {
-        digits_array.rotate_right(16 - n_digits);
+        digits_array.rotate_right(16 - n_digits.min(16));
         Some(FoundNumber {
             consumed: cursor,
-            _n_digits: n_digits,
+            start,
+            n_digits,
             digits: digits_array,
         })
     } else {
@@ -69,42 +470,116 @@ fn extract_digits(input: &[u8]) -> Option<FoundNumber> {
     }
 }
 
-fn convert_digits(digits: &[u8; 16]) -> u32 {
-    let vector = u8x16::from_array(*digits);
-    convert_four_digits(vector)[7] as u32
-}
-
-#[inline]
-#[allow(dead_code)]
-fn convert_two_digits(vector: u8x16) -> [u16; 8] {
-    let two_converted = unsafe { _mm_maddubs_epi16(vector.into(), TWO_DIGITS.into()) };
-    let portable = u16x8::from(two_converted);
-    portable.to_array()
-}
-
-#[inline]
-fn convert_four_digits(vector: u8x16) -> [u16; 8] {
-    let output_vec = unsafe {
-        // Turns u8x16 into u16x8 in the process
-        let two_converted = _mm_maddubs_epi16(vector.into(), TWO_DIGITS.into());
-        // Turns u16x8 into u32x4 in the process
-        let four_converted = _mm_madd_epi16(two_converted, FOUR_DIGITS.into());
-        // Turn u32x4 back into u16x8
-        _mm_packus_epi32(four_converted, four_converted)
-    };
-    let portable = u16x8::from(output_vec);
-    portable.to_array()
+/// Converts up to 16 right-aligned digits one at a time. The zeros in front of them
+/// don't change the value.
+fn convert_digits(digits: &[u8; 16]) -> u64 {
+    digits.iter().fold(0, |n, d| n * 10 + u64::from(*d))
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::separators::Separators;
+
+    #[test]
+    fn stops_at_end_of_line() {
+        assert_eq!(parse_until_newline(b"1 23\n45 6\n"), vec![1, 23]);
+        assert_eq!(parse_until_newline(b"1 23\r\n45 6\r\n"), vec![1, 23]);
+    }
+
+    #[test]
+    fn stops_at_the_configured_line_ending() {
+        let input = b"1 2\n3\r\n4";
+        let crlf = ParseOptions {
+            allowed_separators: Separators::new(LineEnding::CrLf).spaces(),
+            strict: false,
+        };
+        assert_eq!(parse_until_newline_with(input, &crlf), Ok(vec![1, 2, 3]));
+        let lf = ParseOptions::default();
+        assert_eq!(parse_until_newline_with(input, &lf), Ok(vec![1, 2]));
+    }
+
+    #[test]
+    fn checks_the_first_line_against_separators() {
+        let options = ParseOptions::strict(Separators::new(LineEnding::Lf).pipes());
+        assert_eq!(
+            parse_until_newline_with(b"47|53\n1,2", &options),
+            Ok(vec![47, 53])
+        );
+        assert_eq!(
+            parse_until_newline_with(b"47|53,1\n", &options),
+            Err(ParseError {
+                offset: 5,
+                byte: b','
+            })
+        );
+    }
+
+    #[test]
+    fn strict_crlf_mode_rejects_lone_carriage_returns() {
+        let options = ParseOptions::strict(Separators::new(LineEnding::CrLf).spaces());
+        let lone_cr = Err(ParseError {
+            offset: 1,
+            byte: b'\r',
+        });
+        assert_eq!(parse_ints_with_options(b"1\r2", &options), lone_cr);
+        assert_eq!(parse_until_newline_with(b"1\r2", &options), lone_cr);
+        assert_eq!(
+            parse_ints_with_options(b"1\r\n2\r\n", &options),
+            Ok(vec![1, 2])
+        );
+        assert_eq!(
+            parse_until_newline_with(b"1 2\r\n3\r4", &options),
+            Ok(vec![1, 2])
+        );
+    }
+
+    #[test]
+    fn strict_mode_rejects_carriage_returns() {
+        let options = ParseOptions::strict(Separators::default());
+        assert_eq!(
+            parse_ints_with_options(b"1 2\r\n3\n", &options),
+            Err(ParseError {
+                offset: 3,
+                byte: b'\r'
+            })
+        );
+        assert_eq!(
+            parse_ints_with_options(b"1 2\n3\n", &options),
+            Ok(vec![1, 2, 3])
+        );
+    }
+
+    #[test]
+    fn records_spans() {
+        let spans = parse_ints_with_spans(b"12 345\n6");
+        assert_eq!(
+            spans,
+            vec![
+                Span {
+                    value: 12,
+                    start: 0,
+                    len: 2
+                },
+                Span {
+                    value: 345,
+                    start: 3,
+                    len: 3
+                },
+                Span {
+                    value: 6,
+                    start: 7,
+                    len: 1
+                },
+            ]
+        );
+    }
 
     #[test]
     fn finds_leading_digits() {
         let input = "123 \n";
-        let found = extract_digits(input.as_bytes()).unwrap();
-        assert_eq!(found._n_digits, 3);
+        let found = extract_digits(input.as_bytes(), LineEnding::Lf).unwrap();
+        assert_eq!(found.n_digits, 3);
         assert_eq!(found.digits[13], 1);
         assert_eq!(found.digits[14], 2);
         assert_eq!(found.digits[15], 3);
@@ -112,8 +587,8 @@ mod test {
     #[test]
     fn finds_trailing_digits() {
         let input = "    123\n";
-        let found = extract_digits(input.as_bytes()).unwrap();
-        assert_eq!(found._n_digits, 3);
+        let found = extract_digits(input.as_bytes(), LineEnding::Lf).unwrap();
+        assert_eq!(found.n_digits, 3);
         assert_eq!(found.digits[13], 1);
         assert_eq!(found.digits[14], 2);
         assert_eq!(found.digits[15], 3);
@@ -122,33 +597,53 @@ mod test {
     #[test]
     fn finds_middle_digits() {
         let input = "   123 \n";
-        let found = extract_digits(input.as_bytes()).unwrap();
-        assert_eq!(found._n_digits, 3);
+        let found = extract_digits(input.as_bytes(), LineEnding::Lf).unwrap();
+        assert_eq!(found.n_digits, 3);
         assert_eq!(found.digits[13], 1);
         assert_eq!(found.digits[14], 2);
         assert_eq!(found.digits[15], 3);
     }
 
     #[test]
-    fn converts_two_digits_raw() {
-        let mut input = [0; 16];
-        input[14] = 1;
-        input[15] = 2;
-        let vector = u8x16::from_array(input);
-        let output = convert_two_digits(vector);
-        assert_eq!(output[7], 12);
+    fn parses_typed_numbers() {
+        assert_eq!(parse_ints_as::<u8>(b"1 255\n"), Ok(vec![1, 255]));
+        assert_eq!(
+            parse_ints_as::<u8>(b"1 256\n"),
+            Err(IntOverflow {
+                offset: 2,
+                max: u8::MAX.into()
+            })
+        );
+        assert_eq!(
+            parse_ints_as::<u64>(b"18446744073709551616"),
+            Err(IntOverflow {
+                offset: 0,
+                max: u64::MAX
+            })
+        );
+        assert_eq!(
+            parse_hex_ints::<u32>(b"#70c710 #FFFFFF\n"),
+            Ok(vec![0x70c710, 0xffffff])
+        );
+        assert_eq!(parse_bin_ints::<u8>(b"0 101 2"), Ok(vec![0, 5]));
     }
 
     #[test]
-    fn converts_four_digits_raw() {
-        let mut input = [0; 16];
-        input[12] = 1;
-        input[13] = 2;
-        input[14] = 3;
-        input[15] = 4;
-        let vector = u8x16::from_array(input);
-        let output = convert_four_digits(vector);
-        assert_eq!(output[7], 1234);
+    fn joins_integer_and_fraction() {
+        let decimal = |value, scale| Decimal { value, scale };
+        assert_eq!(
+            parse_decimals(b"3.75 -0.125 12 -7 1.2.3 3. .5\n"),
+            vec![
+                decimal(375, 2),
+                decimal(-125, 3),
+                decimal(12, 0),
+                decimal(-7, 0),
+                decimal(12, 1),
+                decimal(3, 0),
+                decimal(3, 0),
+                decimal(5, 0),
+            ]
+        );
     }
 
     #[test]
@@ -189,6 +684,110 @@ mod test {
         assert_eq!(1234, output);
     }
 
+    #[test]
+    fn converts_8_digits() {
+        let mut input = [0; 16];
+        input[8..].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(convert_digits(&input), 12345678);
+    }
+
+    #[test]
+    fn converts_16_digits() {
+        let input = [9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 1, 2, 3, 4, 5, 6];
+        assert_eq!(convert_digits(&input), 9876543210123456);
+        assert_eq!(convert_digits(&[9; 16]), 9999999999999999);
+    }
+
+    #[test]
+    fn parses_5_digit_numbers() {
+        let input = include_bytes!("../../input/day1.txt");
+        let first_line = input.split(|b| *b == b'\n').next().unwrap();
+        assert_eq!(parse_until_newline(input), parse_ints(first_line));
+        assert_eq!(parse_until_newline(b"12345 99999\n1"), vec![12345, 99999]);
+    }
+
+    #[test]
+    fn parses_long_numbers_until_newline() {
+        let input = b"1234567890123456 12345678901234567890 7\n8";
+        assert_eq!(
+            parse_until_newline_u64(input),
+            Ok(vec![1234567890123456, 12345678901234567890, 7])
+        );
+        let wrapped = parse_until_newline(input);
+        assert_eq!(
+            wrapped,
+            vec![
+                1234567890123456u64 as u32,
+                12345678901234567890u64 as u32,
+                7
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_numbers_too_big_for_u64() {
+        assert_eq!(
+            parse_until_newline_u64(b"1 18446744073709551616\n"),
+            Err(IntOverflow {
+                offset: 2,
+                max: u64::MAX
+            })
+        );
+        assert_eq!(
+            parse_until_newline_u64(b"18446744073709551615"),
+            Ok(vec![u64::MAX])
+        );
+    }
+
+    #[test]
+    fn parse_ints_handles_leading_and_trailing_digits() {
+        let input = "12   345\n6789 0";
+        let output = parse_ints(input.as_bytes());
+        assert_eq!(output, vec![12, 345, 6789, 0]);
+    }
+
+    #[test]
+    fn parses_into_existing_vector() {
+        let mut output = vec![7];
+        parse_ints_into(b"1 23\n456", &mut output);
+        assert_eq!(output, vec![7, 1, 23, 456]);
+    }
+
+    #[test]
+    fn parses_to_slice() {
+        let mut output = [0; 4];
+        assert_eq!(parse_ints_to_slice(b"1 23\n456", &mut output), Ok(3));
+        assert_eq!(output, [1, 23, 456, 0]);
+        assert_eq!(
+            parse_ints_to_slice(b"1 2 3 4 5\n", &mut output),
+            Err(OutputTooSmall { n_written: 4 })
+        );
+        assert_eq!(output, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn iterates_like_parse_ints() {
+        let input = b" 1 23,456\n78901234567 ";
+        assert_eq!(IntIter::new(input).collect::<Vec<_>>(), parse_ints(input));
+    }
+
+    #[test]
+    fn parses_long_numbers_as_u64() {
+        let input = "1 123456789012345 42 98765432109876543210\n";
+        let nums = parse_ints_u64(input.as_bytes());
+        let wrapped = 98765432109876543210u128 as u64;
+        assert_eq!(nums, vec![1, 123456789012345, 42, wrapped]);
+    }
+
+    #[test]
+    fn parses_signed_numbers() {
+        let input = "- 1 -- 2 -x3 4- 5-6 --7 -12345";
+        let nums: Vec<i64> = parse_ints_signed(input.as_bytes());
+        assert_eq!(nums, vec![1, 2, 3, 4, 5, -6, -7, -12345]);
+        let nums: Vec<i64> = parse_ints_signed(b"-12345678901 9876543210");
+        assert_eq!(nums, vec![-12345678901, 9876543210]);
+    }
+
     #[test]
     fn terminates_at_end_of_input() {
         // Input with no newline character
@@ -0,0 +1,131 @@
+//! Which bytes are allowed to separate numbers.
+
+/// How lines end in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`
+    Lf,
+    /// `\r\n`. In strict mode a `\r` that isn't followed by a `\n` is rejected.
+    CrLf,
+}
+
+/// A set of ASCII bytes that can separate numbers, always including the line ending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Separators {
+    /// Bit `b` is set when byte `b` is a separator.
+    bits: u128,
+    line_ending: LineEnding,
+}
+
+impl Default for Separators {
+    /// Spaces and `\n`, which is what the puzzle inputs use.
+    fn default() -> Self {
+        Separators::new(LineEnding::Lf).spaces()
+    }
+}
+
+impl Separators {
+    /// Only the bytes of `line_ending`.
+    pub const fn new(line_ending: LineEnding) -> Self {
+        let separators = Separators {
+            bits: 0,
+            line_ending,
+        };
+        match line_ending {
+            LineEnding::Lf => separators.with(b'\n'),
+            LineEnding::CrLf => separators.with(b'\r').with(b'\n'),
+        }
+    }
+
+    /// Adds `byte` to the separators.
+    ///
+    /// Panics if `byte` is a digit or isn't ASCII.
+    pub const fn with(mut self, byte: u8) -> Self {
+        assert!(byte.is_ascii(), "separators must be ASCII");
+        assert!(!byte.is_ascii_digit(), "digits can't be separators");
+        self.bits |= 1 << byte;
+        self
+    }
+
+    /// Adds `' '`.
+    pub const fn spaces(self) -> Self {
+        self.with(b' ')
+    }
+
+    /// Adds `','`.
+    pub const fn commas(self) -> Self {
+        self.with(b',')
+    }
+
+    /// Adds `'|'`.
+    pub const fn pipes(self) -> Self {
+        self.with(b'|')
+    }
+
+    /// Adds `':'`.
+    pub const fn colons(self) -> Self {
+        self.with(b':')
+    }
+
+    pub const fn contains(&self, byte: u8) -> bool {
+        byte.is_ascii() && (self.bits & (1 << byte)) != 0
+    }
+
+    pub const fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Lookup tables for classifying bytes with `pshufb`, indexed by the low and high
+    /// nibble of a byte. A byte is a separator when the entries for its two nibbles
+    /// have a bit in common.
+    ///
+    /// Each of the eight ASCII high nibbles gets its own bit, and the low nibble table
+    /// has that bit set for every low nibble that makes a separator with it. Non-ASCII
+    /// high nibbles have no bits, so those bytes are never separators.
+    pub(crate) const fn nibble_tables(&self) -> ([u8; 16], [u8; 16]) {
+        let mut low = [0; 16];
+        let mut high = [0; 16];
+        let mut h = 0;
+        while h < 8 {
+            high[h] = 1 << h;
+            let mut l = 0;
+            while l < 16 {
+                if self.contains(((h << 4) | l) as u8) {
+                    low[l] |= 1 << h;
+                }
+                l += 1;
+            }
+            h += 1;
+        }
+        (low, high)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn line_endings_are_separators() {
+        let lf = Separators::new(LineEnding::Lf);
+        assert!(lf.contains(b'\n'));
+        assert!(!lf.contains(b'\r'));
+        let crlf = Separators::new(LineEnding::CrLf);
+        assert!(crlf.contains(b'\n'));
+        assert!(crlf.contains(b'\r'));
+    }
+
+    proptest! {
+        #[test]
+        fn nibble_tables_match_contains(bytes in prop::collection::vec(0u8..0x80, 0..10), byte in any::<u8>()) {
+            let separators = bytes
+                .iter()
+                .filter(|b| !b.is_ascii_digit())
+                .fold(Separators::new(LineEnding::Lf), |s, b| s.with(*b));
+            let (low, high) = separators.nibble_tables();
+            let classified = low[usize::from(byte & 0xF)] & high[usize::from(byte >> 4)] != 0;
+            prop_assert_eq!(classified, separators.contains(byte));
+        }
+    }
+}
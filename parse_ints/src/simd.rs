@@ -1,251 +1,61 @@
 #[allow(unused_imports)]
 #[allow(dead_code)]
 use std::arch::x86_64::*;
-use std::{
-    io::{Read, Write},
-    path::Path,
-};
-
-type Error = anyhow::Error;
-
-/// A byte of 0x80 tells the pshufb instruction to put a zero at the corresponding location.
-/// We'll use this as the basis for shuffles and set individual bytes to particular values.
-const ZERO_SHUFFLE: [u8; 16] = [
-    0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
-];
+use std::mem::MaybeUninit;
 
-/// The location of a sequence of digits and how many digits there are.
-struct DigitRange {
-    start: usize,
-    size: usize,
-}
+use crate::{
+    dispatch,
+    options::{ParseError, ParseOptions},
+    scalar::{self, SignedTarget, Span},
+    separators::LineEnding,
+};
 
-/// Which span size a number of digits falls into.
+pub mod avx512;
+mod decimal;
+mod output;
+mod radix;
+// Only the build script and the tests generate the table.
 #[allow(dead_code)]
-fn compute_conversion_size(digits: usize) -> usize {
-    match digits {
-        0 => 0,
-        1 => 1,
-        2 => 2,
-        3 | 4 => 4,
-        5..=8 => 8,
-        _ => panic!("can't convert integers with more than 8 digits"),
-    }
-}
-
-/// How many ranges are consumable from a pattern and at what size.
-#[derive(Clone, Default)]
-struct ConsumableRanges {
-    /// How many ranges are consumable from the pattern.
-    n_ranges: usize,
-    /// At what span size should the digits be consumed.
-    conversion_size: usize,
-}
-
-/// Determine the ranges that are consumable from the total set of ranges
-/// extracted from a pattern.
-fn consumable_ranges(digit_ranges: &[DigitRange]) -> ConsumableRanges {
-    let mut biggest_span_size = 0;
-    let mut n_spans_at_biggest_size = 0;
-    for span_size in [1usize, 2, 4, 8].iter() {
-        let mut consumable_ranges_at_this_size = 0;
-        for dr in digit_ranges.iter() {
-            if dr.size <= *span_size {
-                consumable_ranges_at_this_size += 1;
-            } else {
-                // Stop on the first digit range that won't fit in this span
-                break;
-            }
-        }
-        if (consumable_ranges_at_this_size > 0)
-            && (consumable_ranges_at_this_size > n_spans_at_biggest_size)
-            && (consumable_ranges_at_this_size * span_size <= 16)
-        {
-            biggest_span_size = *span_size;
-            n_spans_at_biggest_size = consumable_ranges_at_this_size;
-        }
-    }
-    ConsumableRanges {
-        n_ranges: n_spans_at_biggest_size,
-        conversion_size: biggest_span_size,
-    }
-}
-
-/// Information about which ranges were extracted from a pattern, at what size,
-/// and whether any bits from the pattern couldn't be consumed.
-struct ExtractedPatternInfo {
-    consumable_ranges: ConsumableRanges,
-    digit_ranges: Vec<DigitRange>,
-    incomplete_bits: usize,
-}
-
-/// Determine the ranges that are extractable from a pattern and whether any bits
-/// couldn't be consumed.
-fn extract_pattern_info(mut pattern: u16) -> ExtractedPatternInfo {
-    let mut digit_ranges = vec![];
-    // A sequence of set bits at the end of the pattern could be an incomplete
-    // number, so we don't want to count those digits.
-    let n_trailing_bits = pattern.trailing_ones();
-    let max_len = 16;
-    let mut shifted = 0;
-    loop {
-        // We need to bring a sequence of 1 bits to the front of the pattern,
-        // so count the leading zeros and shift left by that amount.
-        let useless_zeros = pattern.leading_zeros();
-        shifted += useless_zeros;
-        if (shifted + n_trailing_bits) >= max_len {
-            break;
-        }
-        pattern <<= useless_zeros;
-        // Now that we have a sequence of set bits, count them.
-        let n_digits = pattern.leading_ones();
-        // Record the start position of the digits and how many there are.
-        digit_ranges.push(DigitRange {
-            start: shifted as usize,
-            size: n_digits as usize,
-        });
-        shifted += n_digits;
-        pattern <<= n_digits;
-    }
-    if digit_ranges.is_empty() {
-        return ExtractedPatternInfo {
-            consumable_ranges: ConsumableRanges::default(),
-            digit_ranges: Vec::new(),
-            incomplete_bits: 0,
-        };
-    }
-    let ranges_consumable = consumable_ranges(&digit_ranges);
-    let incomplete_bits = if ranges_consumable.n_ranges < digit_ranges.len() {
-        16 - digit_ranges[ranges_consumable.n_ranges].start
-    } else {
-        n_trailing_bits as usize
-    };
+mod pattern;
+pub mod table_file;
 
-    ExtractedPatternInfo {
-        consumable_ranges: ranges_consumable,
-        digit_ranges,
-        incomplete_bits,
-    }
-}
-
-/// From the consumable ranges of a pattern, generate a shuffle array that can be used
-/// to parse them. Note that a 0x80 byte in the shuffle array means to place a zero in
-/// the destination.
-fn generate_shuffle_array(pat_info: &ExtractedPatternInfo) -> [u8; 16] {
-    let mut shuffle_array = ZERO_SHUFFLE;
-    if pat_info.consumable_ranges.n_ranges == 0 {
-        return shuffle_array;
-    }
-    let conversion_size = pat_info.consumable_ranges.conversion_size;
-    for i in 0..pat_info.consumable_ranges.n_ranges {
-        let digit_range = pat_info.digit_ranges.get(i).unwrap();
-        // Put the cursor at the next block of `conversion_size` bits
-        let output_cursor = conversion_size * i;
-        // We need to pad each set of digits with leading zeros to make it a consistent
-        // number of digits for the entire vector. THE ZERO_SHUFFLE array is filled
-        // with the bytes that generate zeros already, so we need to skip ahead and
-        // only fill out the shuffle pattern bytes that correspond to the actual digits
-        // in the source array.
-        let zero_bits = conversion_size - digit_range.size;
-        for j in 0..digit_range.size {
-            let src = digit_range.start + j;
-            let dest = output_cursor + zero_bits + j;
-            // This cast to u8 should be safe because the value is an index into an array
-            // of size 16, so we should never see a number larger than that.
-            shuffle_array[dest] = src as u8;
-        }
-    }
-    shuffle_array
-}
-
-/// A lookup table entry corresponding to a 16 bit pattern.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
-pub struct PatternData {
-    /// The input array for the `pshufb` instruction
-    shuffle_array: [u8; 16],
-    /// How many trailing bits there are for this pattern
-    skip: u8,
-    /// How many numbers were extracted from this pattern
-    n_extracted: u8,
-    /// The conversion size for this pattern
-    conversion_size: u8,
-}
-
-/// Generate a lookup table for shuffles of every 16 bit pattern.
-#[allow(dead_code)]
-fn generate_pattern_lookup_table() -> Vec<PatternData> {
-    let mut lookup_table = vec![];
-    for i in 0..=u16::MAX {
-        let extracted = extract_pattern_info(i);
-        if extracted.consumable_ranges.n_ranges > 16 {
-            panic!(
-                "found {} ranges from pattern {i:016b}",
-                extracted.consumable_ranges.n_ranges
-            );
-        }
-        let shuffle = generate_shuffle_array(&extracted);
-        let pattern_data = PatternData {
-            shuffle_array: shuffle,
-            // Safe conversion, more than 256 incomplete bits would mean
-            // that we're operating on 256 byte vectors of digits,
-            // but we're only operating on 16 byte vectors.
-            skip: 16 - extracted.incomplete_bits as u8,
-            n_extracted: extracted.consumable_ranges.n_ranges as u8,
-            conversion_size: extracted.consumable_ranges.conversion_size as u8,
-        };
-        lookup_table.push(pattern_data);
-    }
-    lookup_table
-}
+// These live in `options` so the scalar parser can return them off x86 too
+pub use crate::options::{IntOverflow, OutputTooSmall};
+pub use crate::scalar::Decimal;
+pub use decimal::parse_decimals;
+pub use output::ParseTarget;
+pub(crate) use output::{OutputInt, WindowOutput};
+pub use pattern::{PatternData, LOOKUP_TABLE_LEN};
+pub use radix::{parse_bin_ints, parse_hex_ints};
+pub use table_file::{load_lookup_table_from_disk, write_lookup_table, LookupTableError};
 
-/// Serializes the lookup table as a flat array of bytes and saves it to
-/// the provided path.
-#[allow(dead_code)]
-fn write_lookup_table(table: Vec<PatternData>, path: &Path) -> Result<(), Error> {
-    let mut file = std::fs::OpenOptions::new()
-        .create(true)
-        .truncate(true)
-        .write(true)
-        .open(path)
-        .unwrap();
-    let (ptr, length, capacity) = table.into_raw_parts();
-    let n_bytes = length * size_of::<PatternData>();
-    let raw_bytes: Vec<u8> = unsafe {
-        let ptr = ptr as *mut u8;
-        Vec::from_raw_parts(ptr, n_bytes, capacity)
-    };
-    file.write_all(&raw_bytes)?;
-    Ok(())
-}
-
-/// Loads the lookup table from the provided path.
-#[allow(dead_code)]
-fn load_lookup_table_from_disk(path: &Path) -> Result<Vec<PatternData>, Error> {
-    let mut file = std::fs::OpenOptions::new().read(true).open(path).unwrap();
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer)?;
-    let lookup_table = cast_to_lookup_table(buffer);
-    Ok(lookup_table)
-}
-
-/// Reinterprets a vector of bytes as a lookup table.
-pub fn cast_to_lookup_table(bytes: Vec<u8>) -> Vec<PatternData> {
-    let (ptr, _, capacity) = bytes.into_raw_parts();
-    unsafe {
-        let ptr = ptr as *mut PatternData;
-        Vec::from_raw_parts(ptr, 65536, capacity)
-    }
-}
+/// The lookup table for every 16 bit pattern, generated by the build script from
+/// the code in [`pattern`].
+pub static LOOKUP_TABLE: [PatternData; LOOKUP_TABLE_LEN] =
+    include!(concat!(env!("OUT_DIR"), "/lookup_table.rs"));
 
 #[inline]
 pub(crate) fn load_slice_to_vector(bytes: &[u8]) -> __m128i {
     unsafe { _mm_loadu_si128(bytes.as_ptr() as *const __m128i) }
 }
 
+/// Expands a bitmask into a vector with 0xFF in byte `i` when bit `i` is set.
+///
+/// This is `_mm_movm_epi8` without requiring AVX-512: broadcast the low byte of the
+/// mask into the lower 8 bytes and the high byte into the upper 8 bytes, then
+/// check each byte against the single bit it corresponds to.
 #[allow(dead_code)]
 #[inline]
 pub(crate) fn bitmask_to_vector(mask: u16) -> __m128i {
-    unsafe { _mm_movm_epi8(mask) }
+    unsafe {
+        let broadcast = _mm_set1_epi16(mask as i16);
+        let spread = _mm_shuffle_epi8(
+            broadcast,
+            _mm_setr_epi8(0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1),
+        );
+        let bits = _mm_setr_epi8(1, 2, 4, 8, 16, 32, 64, -128, 1, 2, 4, 8, 16, 32, 64, -128);
+        _mm_cmpeq_epi8(_mm_and_si128(spread, bits), bits)
+    }
 }
 
 #[inline]
@@ -267,7 +77,7 @@ pub(crate) fn broadcast_to_vector(byte: u8) -> __m128i {
 pub(crate) fn vector_to_slice(vector: __m128i, buffer: &mut [u8]) {
     let ptr = buffer.as_mut_ptr();
     unsafe {
-        _mm_storeu_epi8(ptr as *mut i8, vector);
+        _mm_storeu_si128(ptr as *mut __m128i, vector);
     }
 }
 
@@ -275,7 +85,7 @@ pub(crate) fn vector_to_slice(vector: __m128i, buffer: &mut [u8]) {
 pub(crate) fn vector_to_slice_16(vector: __m128i, buffer: &mut [u16]) {
     let ptr = buffer.as_mut_ptr();
     unsafe {
-        _mm_storeu_epi16(ptr as *mut i16, vector);
+        _mm_storeu_si128(ptr as *mut __m128i, vector);
     }
 }
 
@@ -283,11 +93,12 @@ pub(crate) fn vector_to_slice_16(vector: __m128i, buffer: &mut [u16]) {
 pub(crate) fn vector_to_slice_32(vector: __m128i, buffer: &mut [u32]) {
     let ptr = buffer.as_mut_ptr();
     unsafe {
-        _mm_storeu_epi32(ptr as *mut i32, vector);
+        _mm_storeu_si128(ptr as *mut __m128i, vector);
     }
 }
 
 /// Returns a mask of the locations of the digits
+#[inline(always)]
 fn detect_digits(input: __m128i) -> __m128i {
     unsafe {
         let ascii_zero = _mm_set1_epi8(b'0' as i8);
@@ -321,40 +132,34 @@ pub(crate) fn print_vec_u32(vector: __m128i, msg: &str) {
     eprintln!("{msg}: {slice:?}");
 }
 
-#[inline]
+#[inline(always)]
 fn shuffle_digits(input: __m128i, pat: &PatternData) -> __m128i {
     let shuffle_vector = load_slice_to_vector(&pat.shuffle_array);
     unsafe { _mm_shuffle_epi8(input, shuffle_vector) }
 }
 
-fn convert_by_1digit(input: __m128i, pat: &PatternData, output: &mut Vec<u32>) {
-    let mut slice = [0; 16];
+#[inline(always)]
+fn convert_by_1digit<T: OutputInt>(input: __m128i, output: &mut WindowOutput<T>) -> bool {
     unsafe {
         let ascii_zero: __m128i = _mm_set1_epi8(b'0' as i8);
         let converted = _mm_subs_epu8(input, ascii_zero);
-        _mm_storeu_epi8(slice.as_mut_ptr(), converted);
-    }
-    for i in 0..pat.n_extracted {
-        output.push(u32::from(slice[i as usize] as u8));
+        T::store_u8s(converted, output)
     }
 }
 
-fn convert_by_2digit(input: __m128i, pat: &PatternData, output: &mut Vec<u32>) {
-    let mut slice: [u16; 8] = [0; 8];
+#[inline(always)]
+fn convert_by_2digit<T: OutputInt>(input: __m128i, output: &mut WindowOutput<T>) -> bool {
     unsafe {
         let ascii_zero = _mm_set1_epi8(b'0' as i8);
         let single_digits = _mm_subs_epu8(input, ascii_zero);
         let weights = _mm_setr_epi8(10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1);
         let two_digits = _mm_maddubs_epi16(single_digits, weights);
-        _mm_storeu_epi16(slice.as_mut_ptr() as *mut i16, two_digits);
-    }
-    for i in 0..pat.n_extracted {
-        output.push(u32::from(slice[i as usize]));
+        T::store_u16s(two_digits, output)
     }
 }
 
-fn convert_by_4digit(input: __m128i, pat: &PatternData, output: &mut Vec<u32>) {
-    let mut slice: [u32; 4] = [0; 4];
+#[inline(always)]
+fn convert_by_4digit<T: OutputInt>(input: __m128i, output: &mut WindowOutput<T>) -> bool {
     unsafe {
         let ascii_zero = _mm_set1_epi8(b'0' as i8);
         let single_digits = _mm_subs_epu8(input, ascii_zero);
@@ -362,15 +167,12 @@ fn convert_by_4digit(input: __m128i, pat: &PatternData, output: &mut Vec<u32>) {
         let two_digits = _mm_maddubs_epi16(single_digits, weights);
         let weights = _mm_setr_epi16(100, 1, 100, 1, 100, 1, 100, 1);
         let four_digits = _mm_madd_epi16(two_digits, weights);
-        _mm_storeu_epi32(slice.as_mut_ptr() as *mut i32, four_digits);
-    }
-    for i in 0..pat.n_extracted {
-        output.push(slice[i as usize]);
+        T::store_u32s(four_digits, output)
     }
 }
 
-fn convert_by_8digit(input: __m128i, pat: &PatternData, output: &mut Vec<u32>) {
-    let mut slice: [u32; 4] = [0; 4];
+#[inline(always)]
+fn convert_by_8digit<T: OutputInt>(input: __m128i, output: &mut WindowOutput<T>) -> bool {
     unsafe {
         // Constants
         let ascii_zero = _mm_set1_epi8(b'0' as i8);
@@ -384,75 +186,757 @@ fn convert_by_8digit(input: __m128i, pat: &PatternData, output: &mut Vec<u32>) {
         // The above gives us 4-digit numbers packed into 32-bit integers,
         // but 4-digit numbers by definition can fit into a 16-bit integer,
         // so we repack those digits into 16-bit integers so we can use the
-        // _mm_maddubs_epi16 instruction again.
-        let four_digits = _mm_packus_epi32(four_digits, four_digits);
+        // _mm_maddubs_epi16 instruction again. The values are at most 9999,
+        // so the signed saturating pack (SSE2) gives the same result as the
+        // unsigned one (SSE4.1) and keeps this path SSSE3-only.
+        let four_digits = _mm_packs_epi32(four_digits, four_digits);
+        let eight_digits = _mm_madd_epi16(four_digits, mul_1_10000);
+        T::store_u32s(eight_digits, output)
+    }
+}
+
+/// Converts a single number of up to 16 digits. The first 8 digits are converted
+/// the same way as [`convert_by_8digit`] and then combined with the last 8 digits as
+/// 64 bit integers.
+#[inline(always)]
+fn convert_by_16digit<T: OutputInt>(input: __m128i, output: &mut WindowOutput<T>) -> bool {
+    unsafe {
+        // Constants
+        let ascii_zero = _mm_set1_epi8(b'0' as i8);
+        let mul_1_10 = _mm_setr_epi8(10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1);
+        let mul_1_100 = _mm_setr_epi16(100, 1, 100, 1, 100, 1, 100, 1);
+        let mul_1_10000 = _mm_setr_epi16(10000, 1, 10000, 1, 10000, 1, 10000, 1);
+        let mul_100000000 = _mm_set1_epi64x(100_000_000);
+        // Conversions
+        let single_digits = _mm_subs_epu8(input, ascii_zero);
+        let two_digits = _mm_maddubs_epi16(single_digits, mul_1_10);
+        let four_digits = _mm_madd_epi16(two_digits, mul_1_100);
+        let four_digits = _mm_packs_epi32(four_digits, four_digits);
         let eight_digits = _mm_madd_epi16(four_digits, mul_1_10000);
-        _mm_storeu_epi32(slice.as_mut_ptr() as *mut i32, eight_digits);
+        // The lowest 64 bits now hold the first 8 digits in the low half and the
+        // last 8 digits in the high half.
+        let upper = _mm_mul_epu32(eight_digits, mul_100000000);
+        let lower = _mm_srli_epi64(eight_digits, 32);
+        let sixteen_digits = _mm_add_epi64(upper, lower);
+        T::store_u64s(sixteen_digits, output)
     }
-    for i in 0..pat.n_extracted {
-        output.push(slice[i as usize]);
+}
+
+/// Shuffles the complete numbers in a window into place and converts them, returning
+/// how many numbers at the front of `output` are real. Numbers too big for `T` wrap.
+#[inline(always)]
+pub(crate) fn convert_window_into<T: OutputInt>(
+    input: __m128i,
+    pattern_data: &PatternData,
+    output: &mut WindowOutput<T>,
+) -> usize {
+    convert_window_checked(input, pattern_data, output).0
+}
+
+/// Like [`convert_window_into`], but also returns whether any of the numbers were too
+/// big for `T`.
+#[inline(always)]
+pub(crate) fn convert_window_checked<T: OutputInt>(
+    input: __m128i,
+    pattern_data: &PatternData,
+    output: &mut WindowOutput<T>,
+) -> (usize, bool) {
+    let shuffled = shuffle_digits(input, pattern_data);
+    let overflowed = match pattern_data.conversion_size {
+        0 => false,
+        1 => convert_by_1digit(shuffled, output),
+        2 => convert_by_2digit(shuffled, output),
+        4 => convert_by_4digit(shuffled, output),
+        8 => convert_by_8digit(shuffled, output),
+        16 => convert_by_16digit(shuffled, output),
+        _ => panic!("invalid conversion size: {}", pattern_data.conversion_size),
+    };
+    (pattern_data.n_extracted as usize, overflowed)
+}
+
+/// Shuffles the complete numbers in a window into place and converts them, appending
+/// them to `output`. This only allocates if `output` doesn't have room for 16 more.
+#[inline(always)]
+fn convert_window<T: OutputInt>(input: __m128i, pattern_data: &PatternData, output: &mut Vec<T>) {
+    output.reserve(16);
+    let len = output.len();
+    let spare: &mut WindowOutput<T> = (&mut output.spare_capacity_mut()[..16]).try_into().unwrap();
+    let n_extracted = convert_window_into(input, pattern_data, spare);
+    // SAFETY: Every entry in the lookup table has no more numbers than its conversion
+    // size has lanes, so the conversion has written at least `n_extracted` numbers.
+    unsafe { output.set_len(len + n_extracted) };
+}
+
+/// Converts the numbers in a chunk, appending them to `output`.
+#[inline(always)]
+fn convert_chunk<T: OutputInt>(bytes: &[u8], chunk: &Chunk, output: &mut Vec<T>) {
+    match chunk {
+        Chunk::Window(window) => convert_window(window.input, window.pattern_data, output),
+        Chunk::LongRun(run) => output.push(T::from_u64_wrapping(run.value(bytes, 10).0)),
     }
 }
 
+/// Parses the integers out of `bytes` 16 bytes at a time.
+///
+/// Numbers of 16 or more digits don't fit in a window and are parsed one digit at a
+/// time instead. Numbers too big for a `u32` wrap.
+///
+/// This uses SSSE3 instructions without checking that the CPU supports them,
+/// use [`crate::dispatch::parse_ints`] unless you've already checked.
+#[inline(always)]
 pub fn parse_ints(bytes: &[u8], lookup_table: &[PatternData]) -> Vec<u32> {
     let mut output = Vec::with_capacity(1024 * 32);
-    let vector_size = 16; // bytes
+    parse_windows(bytes, lookup_table, &mut output);
+    output
+}
+
+/// Parses the integers out of `bytes` 16 bytes at a time, appending them to `output`.
+///
+/// Each window is converted straight into the spare capacity of `output`, which needs
+/// room for 16 numbers past the last one parsed. A vector that's reused across inputs
+/// stops allocating once it's big enough.
+///
+/// This uses SSSE3 instructions without checking that the CPU supports them.
+#[inline(always)]
+pub fn parse_ints_into(bytes: &[u8], lookup_table: &[PatternData], output: &mut Vec<u32>) {
+    parse_windows(bytes, lookup_table, output);
+}
+
+/// Parses the integers out of `bytes` 16 bytes at a time into `output`, returning how
+/// many were written. This never allocates.
+///
+/// Windows are converted straight into `output` while it has room for a whole window,
+/// the last few go through a buffer on the stack. Slots after the returned count may
+/// have been overwritten with scratch values from a window. When the numbers don't all
+/// fit, `output` is filled with as many as fit before returning the error.
+///
+/// This uses SSSE3 instructions without checking that the CPU supports them.
+#[inline(always)]
+pub fn parse_ints_to_slice(
+    bytes: &[u8],
+    lookup_table: &[PatternData],
+    output: &mut [u32],
+) -> Result<usize, OutputTooSmall> {
+    let mut input_cursor = 0;
+    let mut n_written = 0;
+    while let Some(chunk) = next_chunk(bytes, lookup_table, &mut input_cursor) {
+        let remaining = &mut output[n_written..];
+        if remaining.len() >= 16 {
+            // SAFETY: The conversion only ever writes initialized numbers, so viewing
+            // the initialized slice as possibly uninitialized can't un-initialize it.
+            let dest = unsafe { &mut *(remaining.as_mut_ptr() as *mut WindowOutput<u32>) };
+            n_written += chunk.convert_into(bytes, dest);
+        } else {
+            let mut buffer = [MaybeUninit::uninit(); 16];
+            let n_extracted = chunk.convert_into(bytes, &mut buffer);
+            let n_copied = n_extracted.min(remaining.len());
+            for (dest, n) in remaining.iter_mut().zip(&buffer[..n_copied]) {
+                // SAFETY: The first `n_extracted` numbers were written by the conversion.
+                *dest = unsafe { n.assume_init() };
+            }
+            n_written += n_copied;
+            if n_copied < n_extracted {
+                return Err(OutputTooSmall { n_written });
+            }
+        }
+    }
+    Ok(n_written)
+}
+
+/// Parses the integers out of `bytes` using the shared [`LOOKUP_TABLE`] and the fastest
+/// backend this CPU supports, so callers don't need to own or pass a table.
+pub fn parse_ints_default(bytes: &[u8]) -> Vec<u32> {
+    crate::dispatch::parse_ints(bytes, &LOOKUP_TABLE)
+}
+
+/// Parses the integers out of `bytes` 16 bytes at a time, for numbers that don't fit
+/// in a `u32`.
+///
+/// Numbers too big for a `u64` wrap. This uses SSSE3 instructions without checking
+/// that the CPU supports them.
+pub fn parse_ints_u64(bytes: &[u8], lookup_table: &[PatternData]) -> Vec<u64> {
+    let mut output = Vec::with_capacity(1024 * 32);
+    parse_windows(bytes, lookup_table, &mut output);
+    output
+}
+
+/// Parses the integers out of `bytes` 16 bytes at a time as `T`, failing on the first
+/// number that doesn't fit.
+///
+/// The numbers are stored at the width of `T` straight from the conversion, so
+/// `parse_ints_as::<u8>` takes a quarter of the memory of [`parse_ints`]. On CPUs
+/// without SSSE3 this falls back to [`scalar::parse_ints_as`].
+pub fn parse_ints_as<T: ParseTarget>(
+    bytes: &[u8],
+    lookup_table: &[PatternData],
+) -> Result<Vec<T>, IntOverflow> {
+    if dispatch::has_ssse3() {
+        // SAFETY: We just checked that the CPU supports SSSE3.
+        unsafe { parse_ints_as_ssse3(bytes, lookup_table) }
+    } else {
+        scalar::parse_ints_as(bytes)
+    }
+}
+
+#[target_feature(enable = "ssse3")]
+unsafe fn parse_ints_as_ssse3<T: ParseTarget>(
+    bytes: &[u8],
+    lookup_table: &[PatternData],
+) -> Result<Vec<T>, IntOverflow> {
+    parse_checked(
+        bytes,
+        lookup_table,
+        10,
+        detect_digits,
+        convert_window_checked,
+    )
+}
+
+/// Parses `bytes` in 16 byte windows as numbers in `radix`, failing on the first one
+/// that doesn't fit in `T`. `detect` finds the digits in a window and `convert` turns
+/// the shuffled digits into numbers.
+#[inline(always)]
+pub(crate) fn parse_checked<T: OutputInt>(
+    bytes: &[u8],
+    lookup_table: &[PatternData],
+    radix: u32,
+    detect: impl Fn(__m128i) -> __m128i,
+    convert: impl Fn(__m128i, &PatternData, &mut WindowOutput<T>) -> (usize, bool),
+) -> Result<Vec<T>, IntOverflow> {
+    let mut output = Vec::with_capacity(bytes.len() / 2 + 16);
+    let mut input_cursor = 0;
+    while let Some(chunk) = next_chunk_with(bytes, lookup_table, &mut input_cursor, radix, &detect)
+    {
+        let window = match chunk {
+            Chunk::Window(window) => window,
+            Chunk::LongRun(run) => {
+                let (value, wrapped) = run.value(bytes, radix);
+                if wrapped || value > T::MAX {
+                    return Err(IntOverflow {
+                        offset: run.start,
+                        max: T::MAX,
+                    });
+                }
+                output.push(T::from_u64_wrapping(value));
+                continue;
+            }
+        };
+        output.reserve(16);
+        let len = output.len();
+        let spare: &mut WindowOutput<T> =
+            (&mut output.spare_capacity_mut()[..16]).try_into().unwrap();
+        let (n_extracted, overflowed) = convert(window.input, window.pattern_data, spare);
+        if overflowed {
+            return Err(IntOverflow {
+                offset: find_overflow(bytes, window.start, T::MAX, radix).unwrap_or(window.start),
+                max: T::MAX,
+            });
+        }
+        // SAFETY: The conversion has written at least `n_extracted` numbers.
+        unsafe { output.set_len(len + n_extracted) };
+    }
+    Ok(output)
+}
+
+/// Finds where the first number in `radix` bigger than `max` at or after `start`
+/// begins. This is only used once the conversion has found one, so it doesn't need
+/// to be fast.
+fn find_overflow(bytes: &[u8], start: usize, max: u64, radix: u32) -> Option<usize> {
+    let mut value: Option<u64> = None;
+    let mut number_start = start;
+    for (i, b) in bytes.iter().enumerate().skip(start) {
+        if let Some(digit) = char::from(*b).to_digit(radix) {
+            let digit = u64::from(digit);
+            value = match value {
+                None => {
+                    number_start = i;
+                    Some(digit)
+                }
+                Some(v) => Some(v.saturating_mul(radix.into()).saturating_add(digit)),
+            };
+            if value > Some(max) {
+                return Some(number_start);
+            }
+        } else {
+            value = None;
+        }
+    }
+    None
+}
+
+/// Parses `bytes` in 16 byte windows, appending the numbers to `output`.
+#[inline(always)]
+pub(crate) fn parse_windows<T: OutputInt>(
+    bytes: &[u8],
+    lookup_table: &[PatternData],
+    output: &mut Vec<T>,
+) {
+    for_each_chunk(bytes, lookup_table, |chunk| {
+        convert_chunk(bytes, &chunk, output)
+    });
+}
+
+/// A 16 byte window of the input along with what the lookup table knows about it.
+pub(crate) struct Window<'a> {
+    /// The bytes of the window.
+    pub(crate) input: __m128i,
+    /// Where the digits are, with the first byte in the highest bit.
+    pub(crate) digit_bitmask: u16,
+    /// The lookup table entry for `digit_bitmask`.
+    pub(crate) pattern_data: &'a PatternData,
+    /// The offset of the first byte of the window in the input.
+    pub(crate) start: usize,
+}
+
+/// A run of digits too long to fit in a window with a separator after it, which is
+/// parsed one digit at a time instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LongRun {
+    /// The offset of the first digit in the input.
+    pub(crate) start: usize,
+    /// How many digits there are, at least 16.
+    pub(crate) len: usize,
+}
+
+impl LongRun {
+    /// The run of digits in `radix` starting at `start`.
+    #[inline]
+    pub(crate) fn at(bytes: &[u8], start: usize, radix: u32) -> Self {
+        let len = bytes[start..]
+            .iter()
+            .take_while(|b| char::from(**b).is_digit(radix))
+            .count();
+        Self { start, len }
+    }
+
+    /// The value of the run in `radix`, wrapping around past `u64::MAX`, and whether
+    /// it wrapped.
+    pub(crate) fn value(&self, bytes: &[u8], radix: u32) -> (u64, bool) {
+        let mut value = 0u64;
+        let mut wrapped = false;
+        for b in &bytes[self.start..self.start + self.len] {
+            let digit = u64::from(char::from(*b).to_digit(radix).unwrap());
+            let (shifted, o1) = value.overflowing_mul(radix.into());
+            let (added, o2) = shifted.overflowing_add(digit);
+            value = added;
+            wrapped |= o1 | o2;
+        }
+        (value, wrapped)
+    }
+}
+
+/// The next piece of the input that has numbers in it.
+pub(crate) enum Chunk<'a> {
+    Window(Window<'a>),
+    LongRun(LongRun),
+}
+
+impl Chunk<'_> {
+    /// The offset in the input of the first byte of the chunk.
+    #[inline(always)]
+    pub(crate) fn start(&self) -> usize {
+        match self {
+            Chunk::Window(window) => window.start,
+            Chunk::LongRun(run) => run.start,
+        }
+    }
+
+    /// The offset in the input just past the bytes this chunk's numbers came from.
+    #[inline(always)]
+    pub(crate) fn end(&self, n_bytes: usize) -> usize {
+        match self {
+            Chunk::Window(window) => {
+                (window.start + usize::from(window.pattern_data.skip)).min(n_bytes)
+            }
+            Chunk::LongRun(run) => run.start + run.len,
+        }
+    }
+
+    /// Converts the decimal numbers in the chunk, returning how many numbers at the
+    /// front of `output` are real. Numbers too big for `T` wrap.
+    #[inline(always)]
+    pub(crate) fn convert_into<T: OutputInt>(
+        &self,
+        bytes: &[u8],
+        output: &mut WindowOutput<T>,
+    ) -> usize {
+        match self {
+            Chunk::Window(window) => convert_window_into(window.input, window.pattern_data, output),
+            Chunk::LongRun(run) => {
+                output[0] = MaybeUninit::new(T::from_u64_wrapping(run.value(bytes, 10).0));
+                1
+            }
+        }
+    }
+
+    /// Where each number in the chunk starts in the input and how many digits it has,
+    /// in the same order as [`Chunk::convert_into`] puts them.
+    #[inline(always)]
+    pub(crate) fn spans(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let (start, digit_bitmask, n_numbers) = match self {
+            Chunk::Window(window) => (
+                window.start,
+                window.digit_bitmask,
+                usize::from(window.pattern_data.n_extracted),
+            ),
+            Chunk::LongRun(run) => (run.start, 0, 1),
+        };
+        let mut starts = digit_bitmask & !(digit_bitmask >> 1);
+        (0..n_numbers).map(move |_| match self {
+            Chunk::Window(_) => {
+                let offset = starts.leading_zeros();
+                let len = (digit_bitmask << offset).leading_ones();
+                starts &= !(0x8000 >> offset);
+                (start + offset as usize, len as usize)
+            }
+            Chunk::LongRun(run) => (run.start, run.len),
+        })
+    }
+}
+
+/// Walks `bytes` a chunk at a time, calling `f` on each one. The windows at the end are
+/// padded with spaces.
+#[inline(always)]
+pub(crate) fn for_each_chunk(bytes: &[u8], lookup_table: &[PatternData], mut f: impl FnMut(Chunk)) {
+    let mut input_cursor = 0;
+    while let Some(chunk) = next_chunk(bytes, lookup_table, &mut input_cursor) {
+        f(chunk);
+    }
+}
+
+/// Finds the next chunk at or after `input_cursor` that has complete numbers in it,
+/// and moves `input_cursor` past the numbers it contains.
+#[inline(always)]
+pub(crate) fn next_chunk<'a>(
+    bytes: &[u8],
+    lookup_table: &'a [PatternData],
+    input_cursor: &mut usize,
+) -> Option<Chunk<'a>> {
+    next_chunk_with(bytes, lookup_table, input_cursor, 10, detect_digits)
+}
+
+/// Like [`next_chunk`] for numbers in `radix`, with `detect` finding their digits.
+///
+/// `input_cursor` is only ever moved to the start of a run of digits or past the end
+/// of one, so every window starts with a whole number. When a window has no complete
+/// numbers its digits are a single run reaching the end, and the next window starts
+/// where that run does. If the run is the whole window it's a [`LongRun`].
+#[inline(always)]
+pub(crate) fn next_chunk_with<'a>(
+    bytes: &[u8],
+    lookup_table: &'a [PatternData],
+    input_cursor: &mut usize,
+    radix: u32,
+    detect: impl Fn(__m128i) -> __m128i,
+) -> Option<Chunk<'a>> {
     let n_bytes = bytes.len();
+    while *input_cursor < n_bytes {
+        let start = *input_cursor;
+        let input = if start + 16 < n_bytes {
+            load_slice_to_vector(&bytes[start..start + 16])
+        } else {
+            // Pad the leftover bytes with spaces, so a number at the very end is complete
+            let mut extra = [b' '; 16];
+            extra[..n_bytes - start].copy_from_slice(&bytes[start..]);
+            load_slice_to_vector(&extra)
+        };
+        let digit_bitmask = vector_to_bitmask(detect(input));
+        let pattern_data = &lookup_table[digit_bitmask as usize];
+        if pattern_data.n_extracted > 0 {
+            *input_cursor += pattern_data.skip as usize;
+            return Some(Chunk::Window(Window {
+                input,
+                digit_bitmask,
+                pattern_data,
+                start,
+            }));
+        }
+        let run_offset = 16 - digit_bitmask.trailing_ones() as usize;
+        if run_offset > 0 {
+            *input_cursor += run_offset;
+            continue;
+        }
+        let run = LongRun::at(bytes, start, radix);
+        *input_cursor += run.len;
+        return Some(Chunk::LongRun(run));
+    }
+    None
+}
+
+/// An iterator over the integers in a byte slice that decodes one 16 byte window at a
+/// time, so the numbers never need to be collected into a vector.
+///
+/// On CPUs without SSSE3 this hands out the numbers from a [`scalar::IntIter`] instead.
+pub struct IntIter<'a> {
+    bytes: &'a [u8],
+    lookup_table: &'a [PatternData],
+    input_cursor: usize,
+    /// The numbers decoded from the most recent window.
+    decoded: WindowOutput<u32>,
+    /// How many numbers at the front of `decoded` are real.
+    n_decoded: usize,
+    /// How many of `decoded` have been handed out.
+    n_yielded: usize,
+    /// Used instead of the windows when the CPU doesn't support SSSE3.
+    fallback: Option<scalar::IntIter<'a>>,
+}
+
+impl<'a> IntIter<'a> {
+    pub fn new(bytes: &'a [u8], lookup_table: &'a [PatternData]) -> Self {
+        Self {
+            bytes,
+            lookup_table,
+            input_cursor: 0,
+            decoded: [MaybeUninit::uninit(); 16],
+            n_decoded: 0,
+            n_yielded: 0,
+            fallback: (!dispatch::has_ssse3()).then(|| scalar::IntIter::new(bytes)),
+        }
+    }
+
+    /// Decodes the next window with numbers in it, returning `false` at the end of the
+    /// input.
+    #[target_feature(enable = "ssse3")]
+    unsafe fn decode_next(&mut self) -> bool {
+        let Some(chunk) = next_chunk(self.bytes, self.lookup_table, &mut self.input_cursor) else {
+            return false;
+        };
+        self.n_decoded = chunk.convert_into(self.bytes, &mut self.decoded);
+        self.n_yielded = 0;
+        true
+    }
+}
+
+impl Iterator for IntIter<'_> {
+    type Item = u32;
+
+    #[inline]
+    fn next(&mut self) -> Option<u32> {
+        if let Some(fallback) = &mut self.fallback {
+            return fallback.next();
+        }
+        // SAFETY: `new` only leaves out the fallback when the CPU supports SSSE3.
+        if self.n_yielded == self.n_decoded && !unsafe { self.decode_next() } {
+            return None;
+        }
+        // SAFETY: The conversion wrote the first `n_decoded` numbers.
+        let n = unsafe { self.decoded[self.n_yielded].assume_init() };
+        self.n_yielded += 1;
+        Some(n)
+    }
+}
+
+/// A bitmask of the first `n` bytes of a window, with the first byte in the highest bit.
+#[inline(always)]
+pub(crate) fn first_bytes_mask(n: usize) -> u16 {
+    !(u16::MAX.checked_shr(n as u32).unwrap_or(0))
+}
+
+/// Parses the integers out of `bytes` 16 bytes at a time, rejecting any byte that
+/// isn't allowed by `options`.
+///
+/// The check classifies each window with `pshufb` to get a second bitmask of the
+/// separators next to the digit bitmask. Anything in neither, within the part of the
+/// window that was consumed, is an error. Without `strict` this is [`parse_ints`].
+///
+/// On CPUs without SSSE3 this falls back to [`scalar::parse_ints_with_options`].
+pub fn parse_ints_with_options(
+    bytes: &[u8],
+    lookup_table: &[PatternData],
+    options: &ParseOptions,
+) -> Result<Vec<u32>, ParseError> {
+    if dispatch::has_ssse3() {
+        // SAFETY: We just checked that the CPU supports SSSE3.
+        unsafe { parse_ints_with_options_ssse3(bytes, lookup_table, options) }
+    } else {
+        scalar::parse_ints_with_options(bytes, options)
+    }
+}
+
+#[target_feature(enable = "ssse3")]
+unsafe fn parse_ints_with_options_ssse3(
+    bytes: &[u8],
+    lookup_table: &[PatternData],
+    options: &ParseOptions,
+) -> Result<Vec<u32>, ParseError> {
+    if !options.strict {
+        return Ok(parse_ints(bytes, lookup_table));
+    }
+    let (low_table, high_table) = options.allowed_separators.nibble_tables();
+    let low_table = load_slice_to_vector(&low_table);
+    let high_table = load_slice_to_vector(&high_table);
+    let mut output = Vec::with_capacity(1024 * 32);
     let mut input_cursor = 0;
-    while (input_cursor + 16) < n_bytes {
-        let input = load_slice_to_vector(&bytes[input_cursor..(input_cursor + vector_size)]);
-        let digit_vector_mask = detect_digits(input);
-        let digit_bitmask = vector_to_bitmask(digit_vector_mask);
-        let pattern_data = lookup_table[digit_bitmask as usize];
-        if pattern_data.n_extracted == 0 {
-            input_cursor += 16;
+    // Everything before this has been checked
+    let mut checked_to = 0;
+    while let Some(chunk) = next_chunk(bytes, lookup_table, &mut input_cursor) {
+        // Windows without any complete numbers are skipped entirely
+        options.check(bytes, checked_to..chunk.start())?;
+        checked_to = chunk.end(bytes.len());
+        let Chunk::Window(window) = &chunk else {
+            // A long run is nothing but digits
+            convert_chunk(bytes, &chunk, &mut output);
             continue;
+        };
+        let n_consumed = usize::from(window.pattern_data.skip);
+        let n_real = bytes.len() - window.start;
+        let separator_bitmask = classify_separators(window.input, low_table, high_table);
+        let lone_crs = match options.allowed_separators.line_ending() {
+            LineEnding::Lf => 0,
+            LineEnding::CrLf => detect_lone_crs(bytes, window),
+        };
+        let unexpected = (!(window.digit_bitmask | separator_bitmask) | lone_crs)
+            & first_bytes_mask(n_consumed)
+            & first_bytes_mask(n_real);
+        if unexpected != 0 {
+            let offset = window.start + unexpected.leading_zeros() as usize;
+            return Err(ParseError {
+                offset,
+                byte: bytes[offset],
+            });
         }
-        let shuffled = shuffle_digits(input, &pattern_data);
-        match pattern_data.conversion_size {
-            1 => convert_by_1digit(shuffled, &pattern_data, &mut output),
-            2 => convert_by_2digit(shuffled, &pattern_data, &mut output),
-            4 => convert_by_4digit(shuffled, &pattern_data, &mut output),
-            8 => convert_by_8digit(shuffled, &pattern_data, &mut output),
-            _ => panic!("invalid conversion size: {}", pattern_data.conversion_size),
-        }
-        input_cursor += pattern_data.skip as usize;
-    }
-    // Handle any leftover bytes that didn't fit nicely into 16 byte chunks
-    let mut extra = [b' '; 16];
-    let n_leftover_bytes = n_bytes - input_cursor;
-    extra[..n_leftover_bytes].clone_from_slice(&bytes[input_cursor..]);
-    // This is the same as in the loop, minus input_cursor accounting
-    let input = load_slice_to_vector(&extra);
-    let digit_vector_mask = detect_digits(input);
-    let digit_bitmask = vector_to_bitmask(digit_vector_mask);
-    let pattern_data = lookup_table[digit_bitmask as usize];
-    let shuffled = shuffle_digits(input, &pattern_data);
-    match pattern_data.conversion_size {
-        0 => {}
-        1 => convert_by_1digit(shuffled, &pattern_data, &mut output),
-        2 => convert_by_2digit(shuffled, &pattern_data, &mut output),
-        4 => convert_by_4digit(shuffled, &pattern_data, &mut output),
-        8 => convert_by_8digit(shuffled, &pattern_data, &mut output),
-        _ => panic!("invalid conversion size: {}", pattern_data.conversion_size),
+        convert_window(window.input, window.pattern_data, &mut output);
+    }
+    options.check(bytes, checked_to..bytes.len())?;
+    Ok(output)
+}
+
+/// Returns a bitmask of the `\r`s in a window that aren't followed by a `\n`, which
+/// aren't line endings with [`LineEnding::CrLf`].
+#[inline(always)]
+fn detect_lone_crs(bytes: &[u8], window: &Window) -> u16 {
+    let (crs, lfs) = unsafe {
+        (
+            vector_to_bitmask(_mm_cmpeq_epi8(window.input, _mm_set1_epi8(b'\r' as i8))),
+            vector_to_bitmask(_mm_cmpeq_epi8(window.input, _mm_set1_epi8(b'\n' as i8))),
+        )
+    };
+    // The byte after the last one in the window is the first byte of the next one
+    let next_is_lf = bytes.get(window.start + 16) == Some(&b'\n');
+    crs & !((lfs << 1) | u16::from(next_is_lf))
+}
+
+/// Returns a bitmask of the separators in a window, using the nibble lookup tables
+/// from `Separators::nibble_tables`.
+#[inline(always)]
+fn classify_separators(input: __m128i, low_table: __m128i, high_table: __m128i) -> u16 {
+    unsafe {
+        let nibble_mask = _mm_set1_epi8(0x0F);
+        let low_nibbles = _mm_and_si128(input, nibble_mask);
+        let high_nibbles = _mm_and_si128(_mm_srli_epi16(input, 4), nibble_mask);
+        let low_classes = _mm_shuffle_epi8(low_table, low_nibbles);
+        let high_classes = _mm_shuffle_epi8(high_table, high_nibbles);
+        let classes = _mm_and_si128(low_classes, high_classes);
+        let not_separators = _mm_cmpeq_epi8(classes, _mm_setzero_si128());
+        !vector_to_bitmask(not_separators)
     }
+}
+
+/// Parses the same numbers as [`parse_ints`], also recording where each one is.
+///
+/// The positions come from the digit bitmask of each window: a number starts at a set
+/// bit whose neighbour before it is clear, and is as long as the run of set bits there.
+///
+/// On CPUs without SSSE3 this falls back to [`scalar::parse_ints_with_spans`].
+pub fn parse_ints_with_spans(bytes: &[u8], lookup_table: &[PatternData]) -> Vec<Span> {
+    if dispatch::has_ssse3() {
+        // SAFETY: We just checked that the CPU supports SSSE3.
+        unsafe { parse_ints_with_spans_ssse3(bytes, lookup_table) }
+    } else {
+        scalar::parse_ints_with_spans(bytes)
+    }
+}
+
+#[target_feature(enable = "ssse3")]
+unsafe fn parse_ints_with_spans_ssse3(bytes: &[u8], lookup_table: &[PatternData]) -> Vec<Span> {
+    let mut output = Vec::with_capacity(1024 * 32);
+    let mut converted = [MaybeUninit::uninit(); 16];
+    for_each_chunk(bytes, lookup_table, |chunk| {
+        let n_extracted = chunk.convert_into(bytes, &mut converted);
+        for (n, (start, len)) in converted[..n_extracted].iter().zip(chunk.spans()) {
+            output.push(Span {
+                // SAFETY: The first `n_extracted` numbers were written by the conversion.
+                value: unsafe { n.assume_init() },
+                start,
+                len,
+            });
+        }
+    });
     output
 }
 
-#[cfg(test)]
-mod test {
-    use std::sync::LazyLock;
+/// Returns a bitmask of the numbers in a window that have a `-` directly before them,
+/// with the first byte in the highest bit like `digit_bitmask`.
+///
+/// `preceded_by_minus` says whether the byte just before the window is a `-`, since a
+/// number at the very start of the window has its sign in the previous window.
+#[inline(always)]
+fn detect_negative_starts(input: __m128i, digit_bitmask: u16, preceded_by_minus: bool) -> u16 {
+    let minus_vector_mask = unsafe { _mm_cmpeq_epi8(input, _mm_set1_epi8(b'-' as i8)) };
+    let minus_bitmask = vector_to_bitmask(minus_vector_mask);
+    // The bit for the previous byte is one bit higher
+    let starts = digit_bitmask & !(digit_bitmask >> 1);
+    let minus_before = (minus_bitmask >> 1) | (u16::from(preceded_by_minus) << 15);
+    starts & minus_before
+}
 
-    use super::{write_lookup_table, *};
-    use proptest::prelude::*;
+/// Parses the integers out of `bytes`, treating a `-` directly before a number as its
+/// sign. A `-` that isn't directly followed by a digit is a separator like any other
+/// byte, so `5-3` parses as `5, -3` and `- 3` parses as `3`.
+///
+/// The numbers are converted as `u64`s and signed as `i64`s, then narrowed to `T`.
+/// Numbers that don't fit in `T` wrap.
+///
+/// On CPUs without SSSE3 this falls back to [`scalar::parse_ints_signed`].
+pub fn parse_ints_signed<T: SignedTarget>(bytes: &[u8], lookup_table: &[PatternData]) -> Vec<T> {
+    if dispatch::has_ssse3() {
+        // SAFETY: We just checked that the CPU supports SSSE3.
+        unsafe { parse_ints_signed_ssse3(bytes, lookup_table) }
+    } else {
+        scalar::parse_ints_signed(bytes)
+    }
+}
 
-    static LOOKUP_TABLE: LazyLock<Vec<PatternData>> = LazyLock::new(|| {
-        let path = std::env::current_dir()
-            .unwrap()
-            .join("../input/day1_part1_lookup_table.dat");
-        eprintln!("{}", path.display());
-        load_lookup_table_from_disk(&path).unwrap()
+#[target_feature(enable = "ssse3")]
+unsafe fn parse_ints_signed_ssse3<T: SignedTarget>(
+    bytes: &[u8],
+    lookup_table: &[PatternData],
+) -> Vec<T> {
+    let mut output = Vec::with_capacity(1024 * 32);
+    let mut magnitudes: Vec<u64> = Vec::with_capacity(16);
+    for_each_chunk(bytes, lookup_table, |chunk| {
+        let window = match chunk {
+            Chunk::Window(window) => window,
+            Chunk::LongRun(run) => {
+                let negative = run.start > 0 && bytes[run.start - 1] == b'-';
+                let value = scalar::signed(run.value(bytes, 10).0, negative);
+                output.push(T::from_i64_wrapping(value));
+                return;
+            }
+        };
+        magnitudes.clear();
+        convert_window(window.input, window.pattern_data, &mut magnitudes);
+        let preceded_by_minus = window.start > 0 && bytes[window.start - 1] == b'-';
+        let negative_starts =
+            detect_negative_starts(window.input, window.digit_bitmask, preceded_by_minus);
+        // The numbers come out of the window in the same order as their starts
+        let mut starts = window.digit_bitmask & !(window.digit_bitmask >> 1);
+        for magnitude in magnitudes.iter() {
+            let start_bit = 0x8000 >> starts.leading_zeros();
+            let value = scalar::signed(*magnitude, negative_starts & start_bit != 0);
+            output.push(T::from_i64_wrapping(value));
+            starts &= !start_bit;
+        }
     });
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::pattern::*;
+    use super::*;
+    use crate::separators::{LineEnding, Separators};
+    use proptest::prelude::*;
 
     fn shuffle_slice(input: &[u8], lookup_table: &[PatternData]) -> (__m128i, PatternData) {
         eprintln!("input: {input:?}");
@@ -466,6 +950,20 @@ mod test {
         (shuffled, pattern_data)
     }
 
+    /// Runs a single conversion kernel and collects the numbers it extracted.
+    fn convert_with<T: OutputInt>(
+        convert: fn(__m128i, &mut WindowOutput<T>) -> bool,
+        shuffled: __m128i,
+        pat: &PatternData,
+    ) -> Vec<T> {
+        let mut output = [MaybeUninit::uninit(); 16];
+        convert(shuffled, &mut output);
+        output[..pat.n_extracted as usize]
+            .iter()
+            .map(|n| unsafe { n.assume_init() })
+            .collect()
+    }
+
     /// Given an input slice, produce the pattern data for it.
     #[allow(dead_code)]
     fn pattern_data_for_input_slice(input: &[u8; 16], lookup_table: &[PatternData]) -> PatternData {
@@ -475,16 +973,6 @@ mod test {
         lookup_table[digit_bitmask as usize]
     }
 
-    #[test]
-    fn loads_lookup_table_properly() {
-        let lookup_table = generate_pattern_lookup_table();
-        let tempdir = tempfile::tempdir().unwrap();
-        let path = tempdir.path().join("lookup_table.dat");
-        write_lookup_table(lookup_table.clone(), &path).unwrap();
-        let loaded = load_lookup_table_from_disk(&path).unwrap();
-        assert_eq!(lookup_table, loaded);
-    }
-
     proptest! {
         #[test]
         fn identifies_one_digit_conversion_size(idx in 1u16..=15) {
@@ -546,6 +1034,93 @@ mod test {
             };
             prop_assert_eq!(mask, detected_bitmask);
         }
+
+        #[test]
+        fn parses_u64_like_str_parse(nums in prop::collection::vec("[0-9]{1,19}", 0..50)) {
+            // Puzzle inputs end with a newline
+            let mut input = nums.join(" ");
+            input.push('\n');
+            let expected = nums.iter().map(|n| n.parse::<u64>().unwrap()).collect::<Vec<_>>();
+            let parsed = parse_ints_u64(input.as_bytes(), &LOOKUP_TABLE);
+            prop_assert_eq!(expected, parsed);
+        }
+
+        #[test]
+        fn parse_as_matches_str_parse(
+            small in prop::collection::vec(0u64..300, 0..50),
+            medium in prop::collection::vec(0u64..70_000, 0..50),
+            large in prop::collection::vec(0u64..5_000_000_000, 0..50),
+            huge in prop::collection::vec(0u64..1_000_000_000_000_000, 0..50),
+        ) {
+            check_parse_as::<u8>(&small)?;
+            check_parse_as::<u16>(&medium)?;
+            check_parse_as::<u32>(&large)?;
+            check_parse_as::<u64>(&huge)?;
+        }
+
+        #[test]
+        fn typed_entry_points_match_scalar(input in "[0-9a-fA-F .\n-]{0,200}") {
+            let input = input.as_bytes();
+            prop_assert_eq!(parse_ints_as::<u16>(input, &LOOKUP_TABLE), scalar::parse_ints_as::<u16>(input));
+            prop_assert_eq!(parse_hex_ints::<u32>(input, &LOOKUP_TABLE), scalar::parse_hex_ints::<u32>(input));
+            prop_assert_eq!(parse_bin_ints::<u8>(input, &LOOKUP_TABLE), scalar::parse_bin_ints::<u8>(input));
+            prop_assert_eq!(parse_decimals(input, &LOOKUP_TABLE), scalar::parse_decimals(input));
+        }
+
+        #[test]
+        fn spans_match_scalar(nums in prop::collection::vec(0u32..=99_999_999, 0..100), seps in "[ \n,]{1,3}") {
+            let mut input = nums.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(&seps);
+            input.push('\n');
+            let spans = parse_ints_with_spans(input.as_bytes(), &LOOKUP_TABLE);
+            prop_assert_eq!(spans, crate::scalar::parse_ints_with_spans(input.as_bytes()));
+        }
+
+        #[test]
+        fn strict_mode_matches_scalar(input in "[0-9 \n\r,]{0,100}") {
+            let options = ParseOptions::strict(Separators::default());
+            let expected = crate::scalar::parse_ints_with_options(input.as_bytes(), &options);
+            let parsed = parse_ints_with_options(input.as_bytes(), &LOOKUP_TABLE, &options);
+            prop_assert_eq!(parsed.err(), expected.err());
+        }
+
+        #[test]
+        fn strict_crlf_mode_matches_scalar(input in "([0-9 ,\r]|\r\n){0,60}") {
+            let options = ParseOptions::strict(Separators::new(LineEnding::CrLf).spaces());
+            let expected = crate::scalar::parse_ints_with_options(input.as_bytes(), &options);
+            let parsed = parse_ints_with_options(input.as_bytes(), &LOOKUP_TABLE, &options);
+            prop_assert_eq!(parsed, expected);
+        }
+
+        #[test]
+        fn to_slice_matches_scalar(input in "[0-9 \n,]{0,100}", len in 0usize..40) {
+            let mut output = vec![0; len];
+            let mut expected = vec![0; len];
+            let result = parse_ints_to_slice(input.as_bytes(), &LOOKUP_TABLE, &mut output);
+            let expected_result = crate::scalar::parse_ints_to_slice(input.as_bytes(), &mut expected);
+            prop_assert_eq!(result, expected_result);
+            // Anything past the numbers written might be scratch
+            let n_written = result.unwrap_or_else(|e| e.n_written);
+            prop_assert_eq!(&output[..n_written], &expected[..n_written]);
+        }
+
+        #[test]
+        fn u64_and_signed_match_scalar(input in "[0-9 \n,-]{0,100}") {
+            let bytes = input.as_bytes();
+            prop_assert_eq!(parse_ints_u64(bytes, &LOOKUP_TABLE), crate::scalar::parse_ints_u64(bytes));
+            let signed: Vec<i32> = parse_ints_signed(bytes, &LOOKUP_TABLE);
+            prop_assert_eq!(signed, crate::scalar::parse_ints_signed::<i32>(bytes));
+            let signed: Vec<i64> = parse_ints_signed(bytes, &LOOKUP_TABLE);
+            prop_assert_eq!(signed, crate::scalar::parse_ints_signed::<i64>(bytes));
+        }
+
+        #[test]
+        fn parses_signed_numbers_like_str_parse(nums in prop::collection::vec(-9999i32..=9999, 0..50)) {
+            // Puzzle inputs end with a newline
+            let mut input = nums.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(" ");
+            input.push('\n');
+            let parsed: Vec<i32> = parse_ints_signed(input.as_bytes(), &LOOKUP_TABLE);
+            prop_assert_eq!(nums, parsed);
+        }
     }
 
     #[test]
@@ -592,13 +1167,237 @@ mod test {
     }
 
     #[test]
-    #[ignore = "don't write it"]
-    fn dummy_test_write_lookup_table() {
-        let table = generate_pattern_lookup_table();
-        let path = std::env::current_dir()
-            .unwrap()
-            .join("input/2024/day1_part1_lookup_table.dat");
-        write_lookup_table(table, &path).unwrap();
+    fn static_lookup_table_matches_generated() {
+        assert!(generate_pattern_lookup_table() == LOOKUP_TABLE);
+    }
+
+    #[test]
+    fn parses_with_shared_table() {
+        let input = "12 345\n6789 0\n";
+        assert_eq!(parse_ints_default(input.as_bytes()), vec![12, 345, 6789, 0]);
+    }
+
+    #[test]
+    fn iterates_like_parse_ints() {
+        let input = include_bytes!("../../input/day1.txt");
+        let expected = parse_ints(input, &LOOKUP_TABLE);
+        let iterated: Vec<u32> = IntIter::new(input, &LOOKUP_TABLE).collect();
+        assert_eq!(iterated, expected);
+        assert_eq!(IntIter::new(b"", &LOOKUP_TABLE).next(), None);
+    }
+
+    #[test]
+    fn parses_into_existing_vector() {
+        let mut output = vec![7];
+        parse_ints_into(b"12 345 6789\n", &LOOKUP_TABLE, &mut output);
+        assert_eq!(output, vec![7, 12, 345, 6789]);
+    }
+
+    #[test]
+    fn parses_to_slice() {
+        let input = include_bytes!("../../input/day1.txt");
+        let expected = parse_ints(input, &LOOKUP_TABLE);
+        // Exactly the right size, so the last windows can't be written in place
+        let mut output = vec![0; expected.len()];
+        let n_written = parse_ints_to_slice(input, &LOOKUP_TABLE, &mut output).unwrap();
+        assert_eq!(n_written, expected.len());
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn reports_slice_too_small() {
+        let mut output = [0; 3];
+        let result = parse_ints_to_slice(b"1 2 3 4 5\n", &LOOKUP_TABLE, &mut output);
+        assert_eq!(result, Err(OutputTooSmall { n_written: 3 }));
+        assert_eq!(output, [1, 2, 3]);
+        let result = parse_ints_to_slice(b"1 2 3 4 5 6 7 8 9 10 11\n", &LOOKUP_TABLE, &mut output);
+        assert!(result.is_err());
+        // A slice with room for whole windows fills up the same way
+        let input = b"1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20\n";
+        let mut output = [0; 18];
+        let result = parse_ints_to_slice(input, &LOOKUP_TABLE, &mut output);
+        assert_eq!(result, Err(OutputTooSmall { n_written: 18 }));
+        assert_eq!(output.to_vec(), (1..=18).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn parses_narrow_types() {
+        let input = b"1 22 255 4444 65535\n";
+        assert_eq!(
+            parse_ints_as::<u16>(input, &LOOKUP_TABLE),
+            Ok(vec![1, 22, 255, 4444, 65535])
+        );
+        assert_eq!(
+            parse_ints_as::<u8>(input, &LOOKUP_TABLE),
+            Err(IntOverflow {
+                offset: 9,
+                max: 255
+            })
+        );
+        assert_eq!(
+            parse_ints_as::<u32>(b"4294967295 4294967296\n", &LOOKUP_TABLE),
+            Err(IntOverflow {
+                offset: 11,
+                max: u32::MAX.into()
+            })
+        );
+        assert_eq!(
+            parse_ints_as::<u8>(include_bytes!("../../input/day2.txt"), &LOOKUP_TABLE)
+                .map(|v| v.len()),
+            Ok(parse_ints(include_bytes!("../../input/day2.txt"), &LOOKUP_TABLE).len())
+        );
+    }
+
+    /// Checks `parse_ints_as::<T>` against parsing each number with `str::parse`.
+    fn check_parse_as<T: ParseTarget + std::str::FromStr + PartialEq>(
+        nums: &[u64],
+    ) -> Result<(), TestCaseError> {
+        let mut input = String::new();
+        let mut expected = Ok(Vec::new());
+        for n in nums {
+            let offset = input.len();
+            input.push_str(&n.to_string());
+            input.push(' ');
+            if let Ok(parsed) = &mut expected {
+                match n.to_string().parse::<T>() {
+                    Ok(n) => parsed.push(n),
+                    Err(_) => {
+                        expected = Err(IntOverflow {
+                            offset,
+                            max: T::MAX,
+                        })
+                    }
+                }
+            }
+        }
+        prop_assert_eq!(
+            parse_ints_as::<T>(input.as_bytes(), &LOOKUP_TABLE),
+            expected.clone()
+        );
+        prop_assert_eq!(scalar::parse_ints_as::<T>(input.as_bytes()), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn records_spans_like_scalar() {
+        let input = include_bytes!("../../input/day1.txt");
+        let spans = parse_ints_with_spans(input, &LOOKUP_TABLE);
+        assert_eq!(spans, crate::scalar::parse_ints_with_spans(input));
+    }
+
+    #[test]
+    fn strict_mode_finds_unexpected_bytes() {
+        let options = ParseOptions::strict(Separators::default());
+        let mut input = include_bytes!("../../input/day1.txt").to_vec();
+        assert_eq!(
+            parse_ints_with_options(&input, &LOOKUP_TABLE, &options),
+            Ok(parse_ints(&input, &LOOKUP_TABLE))
+        );
+        for offset in [0, 5, 13, 1000, input.len() - 1] {
+            let orig = input[offset];
+            input[offset] = b'\r';
+            assert_eq!(
+                parse_ints_with_options(&input, &LOOKUP_TABLE, &options),
+                Err(ParseError {
+                    offset,
+                    byte: b'\r'
+                })
+            );
+            input[offset] = orig;
+        }
+    }
+
+    #[test]
+    fn strict_mode_tells_separators_apart() {
+        let rules = ParseOptions::strict(Separators::new(LineEnding::Lf).pipes());
+        let updates = ParseOptions::strict(Separators::new(LineEnding::Lf).commas());
+        let rule = b"47|53\n";
+        let update = b"75,47,61\n";
+        assert_eq!(
+            parse_ints_with_options(rule, &LOOKUP_TABLE, &rules),
+            Ok(vec![47, 53])
+        );
+        assert_eq!(
+            parse_ints_with_options(update, &LOOKUP_TABLE, &updates),
+            Ok(vec![75, 47, 61])
+        );
+        assert_eq!(
+            parse_ints_with_options(update, &LOOKUP_TABLE, &rules),
+            Err(ParseError {
+                offset: 2,
+                byte: b','
+            })
+        );
+        assert_eq!(
+            parse_ints_with_options(rule, &LOOKUP_TABLE, &updates),
+            Err(ParseError {
+                offset: 2,
+                byte: b'|'
+            })
+        );
+    }
+
+    #[test]
+    fn strict_mode_accepts_crlf_when_configured() {
+        let input = b"1 2\r\n3 4\r\n";
+        let lf = ParseOptions::strict(Separators::default());
+        let crlf = ParseOptions::strict(Separators::new(LineEnding::CrLf).spaces());
+        assert!(parse_ints_with_options(input, &LOOKUP_TABLE, &lf).is_err());
+        assert_eq!(
+            parse_ints_with_options(input, &LOOKUP_TABLE, &crlf),
+            Ok(vec![1, 2, 3, 4])
+        );
+    }
+
+    #[test]
+    fn strict_crlf_mode_rejects_lone_carriage_returns() {
+        let crlf = ParseOptions::strict(Separators::new(LineEnding::CrLf).spaces());
+        assert_eq!(
+            parse_ints_with_options(b"1\r2", &LOOKUP_TABLE, &crlf),
+            Err(ParseError {
+                offset: 1,
+                byte: b'\r'
+            })
+        );
+        // A `\r\n` split across two windows is still a line ending, a lone `\r` at the
+        // end of a window or of the input isn't
+        let input = b"1 2 3 4 5 6 7 8\r\n9\r\n";
+        assert_eq!(input[15], b'\r');
+        assert_eq!(
+            parse_ints_with_options(input, &LOOKUP_TABLE, &crlf),
+            Ok((1..=9).collect())
+        );
+        for (input, offset) in [
+            (&b"1 2 3 4 5 6 7 8\r 9\r\n"[..], 15),
+            (b"1 2 3 4 5 6 7 8\r\n9\r", 18),
+        ] {
+            let expected = crate::scalar::parse_ints_with_options(input, &crlf);
+            assert_eq!(
+                expected,
+                Err(ParseError {
+                    offset,
+                    byte: b'\r'
+                })
+            );
+            assert_eq!(
+                parse_ints_with_options(input, &LOOKUP_TABLE, &crlf),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn strict_mode_rejects_bom() {
+        let options = ParseOptions::strict(Separators::default());
+        let input = b"\xEF\xBB\xBF1 2\n";
+        let err = parse_ints_with_options(input, &LOOKUP_TABLE, &options).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError {
+                offset: 0,
+                byte: 0xEF
+            }
+        );
     }
 
     #[test]
@@ -677,8 +1476,7 @@ mod test {
             b'1', b' ',
         ];
         let (shuffled, pat) = shuffle_slice(&input, &LOOKUP_TABLE);
-        let mut output = Vec::new();
-        convert_by_1digit(shuffled, &pat, &mut output);
+        let output: Vec<u32> = convert_with(convert_by_1digit, shuffled, &pat);
         assert_eq!(output, vec![1, 1, 1, 1, 1, 1, 1, 1]);
     }
 
@@ -690,8 +1488,7 @@ mod test {
             b' ', b' ',
         ];
         let (shuffled, pat) = shuffle_slice(&input, &LOOKUP_TABLE);
-        let mut output = Vec::new();
-        convert_by_2digit(shuffled, &pat, &mut output);
+        let output: Vec<u32> = convert_with(convert_by_2digit, shuffled, &pat);
         assert_eq!(output, vec![11, 11, 11, 11, 11]);
     }
 
@@ -703,8 +1500,7 @@ mod test {
             b'1', b' ',
         ];
         let (shuffled, pat) = shuffle_slice(&input, &LOOKUP_TABLE);
-        let mut output = Vec::new();
-        convert_by_4digit(shuffled, &pat, &mut output);
+        let output: Vec<u32> = convert_with(convert_by_4digit, shuffled, &pat);
         assert_eq!(output, vec![111, 111, 111, 111]);
     }
 
@@ -716,8 +1512,7 @@ mod test {
             b' ', b' ', b' ', b' ',
         ];
         let (shuffled, pat) = shuffle_slice(&input, &LOOKUP_TABLE);
-        let mut output = Vec::new();
-        convert_by_4digit(shuffled, &pat, &mut output);
+        let output: Vec<u32> = convert_with(convert_by_4digit, shuffled, &pat);
         assert_eq!(output, vec![1111, 1111, 1111]);
     }
 
@@ -729,8 +1524,7 @@ mod test {
             b' ', b' ', b' ', b' ',
         ];
         let (shuffled, pat) = shuffle_slice(&input, &LOOKUP_TABLE);
-        let mut output = Vec::new();
-        convert_by_4digit(shuffled, &pat, &mut output);
+        let output: Vec<u32> = convert_with(convert_by_4digit, shuffled, &pat);
         assert_eq!(output, vec![1234, 1111, 1111]);
     }
 
@@ -744,11 +1538,146 @@ mod test {
         let (shuffled, pat) = shuffle_slice(&input, &LOOKUP_TABLE);
         let mut shuffled_array = [0; 16];
         vector_to_slice(shuffled, &mut shuffled_array);
-        let mut output = Vec::new();
-        convert_by_8digit(shuffled, &pat, &mut output);
+        let output: Vec<u32> = convert_with(convert_by_8digit, shuffled, &pat);
         assert_eq!(output, vec![11111111]);
     }
 
+    #[test]
+    fn conversion_size_long_number() {
+        // 123456789_1_2___
+        let pattern = 0b1111111110101000;
+        let pat_info = extract_pattern_info(pattern);
+        assert_eq!(pat_info.consumable_ranges.n_ranges, 1);
+        assert_eq!(pat_info.consumable_ranges.conversion_size, 16);
+        assert_eq!(pat_info.incomplete_bits, 6);
+    }
+
+    #[test]
+    fn conversion_size_caps_ranges_to_vector_size() {
+        // 123456_8_9_10_11
+        let pattern = 0b1111110101011011;
+        let pat_info = extract_pattern_info(pattern);
+        assert_eq!(pat_info.consumable_ranges.n_ranges, 2);
+        assert_eq!(pat_info.consumable_ranges.conversion_size, 8);
+    }
+
+    #[test]
+    fn converts_16digit_pattern() {
+        // 123456789012345_
+        let input = b"123456789012345 ";
+        let (shuffled, pat) = shuffle_slice(input, &LOOKUP_TABLE);
+        let output: Vec<u64> = convert_with(convert_by_16digit, shuffled, &pat);
+        assert_eq!(output, vec![123456789012345]);
+    }
+
+    #[test]
+    fn parses_long_numbers_as_u64() {
+        let input = "1 123456789012345 42 9876543210\n";
+        let nums = parse_ints_u64(input.as_bytes(), &LOOKUP_TABLE);
+        assert_eq!(nums, vec![1, 123456789012345, 42, 9876543210]);
+    }
+
+    #[test]
+    fn parses_signed_numbers() {
+        let input = "-1 2 -34 -5678 90 -12345";
+        let nums: Vec<i32> = parse_ints_signed(input.as_bytes(), &LOOKUP_TABLE);
+        assert_eq!(nums, vec![-1, 2, -34, -5678, 90, -12345]);
+    }
+
+    #[test]
+    fn parses_numbers_outside_i32_as_i64() {
+        let input = b"-12345678901 9876543210 -2147483649\n";
+        let nums: Vec<i64> = parse_ints_signed(input, &LOOKUP_TABLE);
+        assert_eq!(nums, vec![-12345678901, 9876543210, -2147483649]);
+        let nums: Vec<i32> = parse_ints_signed(input, &LOOKUP_TABLE);
+        assert_eq!(
+            nums,
+            vec![-12345678901i64 as i32, 9876543210i64 as i32, i32::MAX]
+        );
+    }
+
+    #[test]
+    fn parses_i32_min() {
+        let nums: Vec<i32> = parse_ints_signed(b"-2147483648 7\n", &LOOKUP_TABLE);
+        assert_eq!(nums, vec![i32::MIN, 7]);
+    }
+
+    #[test]
+    fn parses_signed_numbers_as_i64() {
+        let input = "p=-12,3 v=4,-56";
+        let nums: Vec<i64> = parse_ints_signed(input.as_bytes(), &LOOKUP_TABLE);
+        assert_eq!(nums, vec![-12, 3, 4, -56]);
+    }
+
+    #[test]
+    fn ignores_minus_without_digits() {
+        let input = "- 1 -- 2 -x3 4- 5-6 --7";
+        let nums: Vec<i32> = parse_ints_signed(input.as_bytes(), &LOOKUP_TABLE);
+        assert_eq!(nums, vec![1, 2, 3, 4, 5, -6, -7]);
+    }
+
+    #[test]
+    fn parses_sign_in_previous_window() {
+        // The first window stops at the `1`, so the `-` is in the previous window
+        let input = "2 3 4 5 6 7 8 -123 9";
+        let nums: Vec<i32> = parse_ints_signed(input.as_bytes(), &LOOKUP_TABLE);
+        assert_eq!(nums, vec![2, 3, 4, 5, 6, 7, 8, -123, 9]);
+    }
+
+    #[test]
+    fn detects_negative_starts() {
+        let input = load_slice_to_vector(b"-1 2 -34--5 -   ");
+        let digit_bitmask = vector_to_bitmask(detect_digits(input));
+        let negative_starts = detect_negative_starts(input, digit_bitmask, false);
+        assert_eq!(negative_starts, 0b0100_0010_0010_0000);
+    }
+
+    #[test]
+    fn parses_a_long_number_at_every_offset() {
+        let digits = b"1234567890123456789";
+        for len in 1..=digits.len() {
+            let number = &digits[..len];
+            let value: u64 = std::str::from_utf8(number).unwrap().parse().unwrap();
+            // Leave room for the sign
+            for offset in 1..=48 - len {
+                let mut input = [b'_'; 48];
+                input[offset..offset + len].copy_from_slice(number);
+                input[offset - 1] = b'-';
+                assert_eq!(parse_ints_u64(&input, &LOOKUP_TABLE), vec![value]);
+                let signed: Vec<i64> = parse_ints_signed(&input, &LOOKUP_TABLE);
+                assert_eq!(signed, vec![-(value as i64)]);
+                let narrowed: Vec<i32> = parse_ints_signed(&input, &LOOKUP_TABLE);
+                assert_eq!(narrowed, vec![-(value as i64) as i32]);
+                input[offset - 1] = b'+';
+                let signed: Vec<i64> = parse_ints_signed(&input, &LOOKUP_TABLE);
+                assert_eq!(signed, vec![value as i64]);
+                let spans = parse_ints_with_spans(&input, &LOOKUP_TABLE);
+                assert_eq!((spans[0].start, spans[0].len), (offset, len));
+                assert_eq!(parse_ints_as::<u64>(&input, &LOOKUP_TABLE), Ok(vec![value]));
+            }
+        }
+    }
+
+    #[test]
+    fn reports_overflow_in_long_runs() {
+        let input = b"1 99999999999999999999 2";
+        assert_eq!(
+            parse_ints_as::<u64>(input, &LOOKUP_TABLE),
+            Err(IntOverflow {
+                offset: 2,
+                max: u64::MAX
+            })
+        );
+        let input = b"1 4294967296000000 2";
+        assert_eq!(
+            parse_ints_as::<u32>(input, &LOOKUP_TABLE),
+            Err(IntOverflow {
+                offset: 2,
+                max: u32::MAX.into()
+            })
+        );
+    }
+
     #[test]
     fn handles_end_condition() {
         let input = "____1234________eee";
@@ -0,0 +1,374 @@
+//! A parser that works on 64 byte windows using AVX-512.
+//!
+//! Instead of looking up a shuffle for each 16 bit digit pattern, we find where
+//! each number starts and ends directly from the 64 bit digit mask. `vpcompressb`
+//! (AVX-512 VBMI2) packs the start and end positions of the numbers into the front of
+//! a vector, from which we build the indices for a single `vpermb` (AVX-512 VBMI)
+//! that gathers up to 8 numbers into right-aligned 8 byte slots. A zero-masked
+//! permute fills the padding in each slot with zeros, so there's no lookup table
+//! involved at all.
+use std::arch::x86_64::*;
+
+/// How many numbers are gathered by a single permute.
+const BATCH_SIZE: usize = 8;
+
+/// How many digits fit in one slot of a batch.
+const SLOT_SIZE: u32 = 8;
+
+/// Converts the ASCII digits in a window to their values and returns them along
+/// with a bitmask that has bit `i` set when byte `i` is a digit.
+#[inline(always)]
+fn detect_digits_64(input: __m512i) -> (__m512i, u64) {
+    unsafe {
+        // Anything that isn't a digit wraps around to a value >= 10
+        let digits = _mm512_sub_epi8(input, _mm512_set1_epi8(b'0' as i8));
+        let mask = _mm512_cmplt_epu8_mask(digits, _mm512_set1_epi8(10));
+        (digits, mask)
+    }
+}
+
+/// Converts a run of ASCII digits one at a time, for numbers that don't fit in a slot.
+#[inline]
+fn convert_slow(digits: &[u8]) -> u32 {
+    digits.iter().fold(0u32, |acc, byte| {
+        acc.wrapping_mul(10).wrapping_add(u32::from(byte - b'0'))
+    })
+}
+
+/// Converts 8 right-aligned 8 digit numbers and appends the first `n` to `output`.
+#[inline(always)]
+fn convert_slots(gathered: __m512i, n: usize, output: &mut Vec<u32>) {
+    output.reserve(BATCH_SIZE);
+    unsafe {
+        let mul_1_10 = _mm512_set1_epi16(0x010A);
+        let mul_1_100 = _mm512_set1_epi32(0x00010064);
+        let two_digits = _mm512_maddubs_epi16(gathered, mul_1_10);
+        let four_digits = _mm512_madd_epi16(two_digits, mul_1_100);
+        // Each 64 bit lane now has the upper four digits in its low half and the
+        // lower four digits in its high half.
+        let upper = _mm512_mul_epu32(four_digits, _mm512_set1_epi64(10000));
+        let lower = _mm512_srli_epi64::<32>(four_digits);
+        let eight_digits = _mm512_add_epi64(upper, lower);
+        let packed = _mm512_cvtepi64_epi32(eight_digits);
+        // Store all 8 slots into the spare capacity and only keep the filled ones.
+        let dest = output.as_mut_ptr().add(output.len());
+        _mm256_storeu_si256(dest as *mut __m256i, packed);
+        output.set_len(output.len() + n);
+    }
+}
+
+/// Whether any run of digits in `runs` is too long to fit in a slot.
+#[inline(always)]
+fn has_long_run(runs: u64) -> bool {
+    let mut long = runs;
+    for _ in 0..SLOT_SIZE {
+        long &= long >> 1;
+    }
+    long != 0
+}
+
+/// Converts the first batch of numbers in `runs`, which must only contain complete
+/// runs of at most 8 digits.
+///
+/// Returns the start of the first run that wasn't converted, if any.
+#[inline(always)]
+fn parse_short_runs(digits: __m512i, runs: u64, output: &mut Vec<u32>) -> Option<u32> {
+    let starts_mask = runs & !(runs << 1);
+    let ends_mask = runs & !(runs >> 1);
+    let n_runs = starts_mask.count_ones() as usize;
+    let gathered = unsafe {
+        let iota = _mm512_set_epi8(
+            63, 62, 61, 60, 59, 58, 57, 56, 55, 54, 53, 52, 51, 50, 49, 48, 47, 46, 45, 44, 43, 42,
+            41, 40, 39, 38, 37, 36, 35, 34, 33, 32, 31, 30, 29, 28, 27, 26, 25, 24, 23, 22, 21, 20,
+            19, 18, 17, 16, 15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+        );
+        // Byte `k` holds the start/end of the `k`th number
+        let starts = _mm512_maskz_compress_epi8(starts_mask, iota);
+        let ends = _mm512_maskz_compress_epi8(ends_mask, iota);
+        // Spread those out so every byte of slot `k` holds the start/end of number `k`
+        let slot_of_byte = _mm512_srli_epi16::<3>(_mm512_and_si512(iota, _mm512_set1_epi8(0x38)));
+        let starts = _mm512_permutexvar_epi8(slot_of_byte, starts);
+        let ends = _mm512_permutexvar_epi8(slot_of_byte, ends);
+        // Slot bytes 0..8 pull from window bytes end-7..=end. Indices before the start
+        // of the number are padding, including the ones that go negative, and
+        // `vpermb` only looks at the low 6 bits of the ones we keep.
+        let offset_in_slot = _mm512_and_si512(iota, _mm512_set1_epi8(0x07));
+        let back_to_first = _mm512_sub_epi8(offset_in_slot, _mm512_set1_epi8(SLOT_SIZE as i8 - 1));
+        let indices = _mm512_add_epi8(ends, back_to_first);
+        let keep = _mm512_cmpge_epi8_mask(indices, starts);
+        _mm512_maskz_permutexvar_epi8(keep, indices, digits)
+    };
+    convert_slots(gathered, n_runs.min(BATCH_SIZE), output);
+    if n_runs > BATCH_SIZE {
+        let mut remaining = starts_mask;
+        for _ in 0..BATCH_SIZE {
+            remaining &= remaining - 1;
+        }
+        Some(remaining.trailing_zeros())
+    } else {
+        None
+    }
+}
+
+/// The numbers that will be gathered by the next permute, for windows that contain
+/// numbers too long for a slot.
+#[derive(Default)]
+struct Batch {
+    /// Permute indices, one `u64` of 8 byte indices per slot.
+    indices: [u64; BATCH_SIZE],
+    /// Which bytes of the permuted vector hold digits rather than padding.
+    keep: u64,
+    /// How many slots are filled.
+    len: usize,
+}
+
+impl Batch {
+    /// Adds the number whose last digit is at `end` to the next slot.
+    #[inline(always)]
+    fn push(&mut self, end: u32, n_digits: u32) {
+        // Slot bytes 0..8 pull from window bytes end-7..=end. The indices may wrap
+        // below zero for numbers near the start of the window, but those bytes are
+        // masked off anyway and `vpermb` only looks at the low 6 bits. Adding 64
+        // keeps each byte positive so nothing carries into its neighbor.
+        let first = u64::from(end + 64 - (SLOT_SIZE - 1));
+        self.indices[self.len] = 0x0706050403020100 + first * 0x0101010101010101;
+        let slot_mask = ((1u64 << n_digits) - 1) << (SLOT_SIZE - n_digits);
+        self.keep |= slot_mask << (self.len * SLOT_SIZE as usize);
+        self.len += 1;
+    }
+
+    fn is_full(&self) -> bool {
+        self.len == BATCH_SIZE
+    }
+
+    /// Gathers, converts, and stores the numbers in this batch, then empties it.
+    #[inline(always)]
+    fn flush(&mut self, digits: __m512i, output: &mut Vec<u32>) {
+        if self.len == 0 {
+            return;
+        }
+        let gathered = unsafe {
+            let indices = _mm512_loadu_si512(self.indices.as_ptr() as *const __m512i);
+            _mm512_maskz_permutexvar_epi8(self.keep, indices, digits)
+        };
+        convert_slots(gathered, self.len, output);
+        *self = Batch::default();
+    }
+}
+
+/// Converts up to one batch worth of the runs of digits in `runs`, which must only
+/// contain complete runs. Numbers that don't fit in a slot are converted one digit at
+/// a time.
+///
+/// Returns the start of the first run that wasn't converted, if any.
+#[inline(always)]
+fn parse_runs(window: &[u8], digits: __m512i, mut runs: u64, output: &mut Vec<u32>) -> Option<u32> {
+    let mut batch = Batch::default();
+    while runs != 0 {
+        let start = runs.trailing_zeros();
+        if batch.is_full() {
+            batch.flush(digits, output);
+            return Some(start);
+        }
+        let n_digits = (runs >> start).trailing_ones();
+        let end = start + n_digits - 1;
+        if n_digits <= SLOT_SIZE {
+            batch.push(end, n_digits);
+        } else {
+            // Keep the output in order
+            batch.flush(digits, output);
+            output.push(convert_slow(&window[start as usize..=end as usize]));
+        }
+        // Clear the run we just handled. `end` is at most 63, so this doesn't overflow.
+        runs &= !((u64::MAX >> (63 - end)) >> start << start);
+    }
+    batch.flush(digits, output);
+    None
+}
+
+/// Converts the first batch of numbers in `runs`, which must only contain complete runs.
+///
+/// Returns the start of the first run that wasn't converted, if any.
+#[inline(always)]
+fn parse_window(window: &[u8], digits: __m512i, runs: u64, output: &mut Vec<u32>) -> Option<u32> {
+    if has_long_run(runs) {
+        parse_runs(window, digits, runs, output)
+    } else {
+        parse_short_runs(digits, runs, output)
+    }
+}
+
+/// Parses the integers out of `bytes` 64 bytes at a time.
+///
+/// This uses AVX-512 F, BW, VBMI, and VBMI2 instructions without checking that the CPU
+/// supports them, use [`crate::dispatch::parse_ints`] unless you've already checked.
+#[inline(always)]
+pub fn parse_ints(bytes: &[u8]) -> Vec<u32> {
+    let mut output = Vec::with_capacity(1024 * 32);
+    parse_windows(bytes, &mut output, |_| {});
+    output
+}
+
+/// Walks `bytes` 64 bytes at a time like [`parse_ints`], calling `f` with the numbers
+/// converted from each window instead of collecting them.
+#[inline(always)]
+pub(crate) fn for_each_window(bytes: &[u8], mut f: impl FnMut(&[u32])) {
+    let mut converted = Vec::with_capacity(2 * BATCH_SIZE);
+    parse_windows(bytes, &mut converted, |converted| {
+        f(converted);
+        converted.clear();
+    });
+}
+
+/// Appends the integers in `bytes` to `output`, calling `after_window` each time a
+/// window has been converted.
+#[inline(always)]
+fn parse_windows(bytes: &[u8], output: &mut Vec<u32>, mut after_window: impl FnMut(&mut Vec<u32>)) {
+    let vector_size = 64; // bytes
+    let n_bytes = bytes.len();
+    let mut input_cursor = 0;
+    while (input_cursor + vector_size) <= n_bytes {
+        let window = &bytes[input_cursor..(input_cursor + vector_size)];
+        let input = unsafe { _mm512_loadu_si512(window.as_ptr() as *const __m512i) };
+        let (digits, digit_mask) = detect_digits_64(input);
+        // A run of digits that reaches the end of the window might continue into the
+        // next one, so leave it for the next window to start on.
+        let incomplete_start = 64 - digit_mask.leading_ones();
+        if incomplete_start == 0 {
+            // The whole window is digits, find the end of the number the slow way.
+            let n_digits = bytes[input_cursor..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .count();
+            let end = input_cursor + n_digits;
+            output.push(convert_slow(&bytes[input_cursor..end]));
+            after_window(output);
+            input_cursor = end;
+            continue;
+        }
+        let complete_runs = digit_mask & (u64::MAX >> (64 - incomplete_start));
+        // Starting the next window at the first number we didn't get to is cheaper
+        // than doing a second permute on this one.
+        let next_start = parse_window(window, digits, complete_runs, output);
+        after_window(output);
+        input_cursor += next_start.unwrap_or(incomplete_start) as usize;
+    }
+    // Handle the leftover bytes, every run in here is complete
+    let mut extra = [b' '; 64];
+    let n_leftover_bytes = n_bytes - input_cursor;
+    extra[..n_leftover_bytes].clone_from_slice(&bytes[input_cursor..]);
+    let input = unsafe { _mm512_loadu_si512(extra.as_ptr() as *const __m512i) };
+    let (digits, mut digit_mask) = detect_digits_64(input);
+    while let Some(next_start) = parse_window(&extra, digits, digit_mask, output) {
+        after_window(output);
+        digit_mask &= u64::MAX << next_start;
+    }
+    after_window(output);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dispatch::{parse_ints_with, Backend};
+    use proptest::prelude::*;
+
+    fn has_avx512() -> bool {
+        crate::dispatch::can_test(Backend::Avx512)
+    }
+
+    fn reference(input: &[u8]) -> Vec<u32> {
+        parse_ints_with(Backend::Scalar, input, &[])
+    }
+
+    #[test]
+    fn parses_pattern_2() {
+        if !has_avx512() {
+            return;
+        }
+        let input = "__123__12345____";
+        assert_eq!(parse_ints(input.as_bytes()), vec![123, 12345]);
+    }
+
+    #[test]
+    fn parses_more_than_one_batch() {
+        if !has_avx512() {
+            return;
+        }
+        let input = "1 2 3 4 5 6 7 8 9 10 11 12";
+        let expected: Vec<u32> = (1..=12).collect();
+        assert_eq!(parse_ints(input.as_bytes()), expected);
+    }
+
+    #[test]
+    fn parses_number_across_window_boundary() {
+        if !has_avx512() {
+            return;
+        }
+        let mut input = vec![b' '; 60];
+        input.extend_from_slice(b"12345678 9");
+        assert_eq!(parse_ints(&input), vec![12345678, 9]);
+    }
+
+    #[test]
+    fn detects_long_runs() {
+        assert!(!has_long_run(0b0111_1111_1011));
+        assert!(has_long_run(0b1_1111_1111_0000));
+    }
+
+    #[test]
+    fn parses_many_short_numbers() {
+        if !has_avx512() {
+            return;
+        }
+        let input = "1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28";
+        let expected: Vec<u32> = (1..=28).collect();
+        assert_eq!(parse_ints(input.as_bytes()), expected);
+    }
+
+    #[test]
+    fn parses_long_numbers_in_order() {
+        if !has_avx512() {
+            return;
+        }
+        let input = "1 1234567890 2 ";
+        assert_eq!(parse_ints(input.as_bytes()), reference(input.as_bytes()));
+    }
+
+    #[test]
+    fn parses_window_of_only_digits() {
+        if !has_avx512() {
+            return;
+        }
+        let mut input = vec![b'1'; 70];
+        input.extend_from_slice(b" 42");
+        assert_eq!(parse_ints(&input), reference(&input));
+    }
+
+    #[test]
+    fn matches_16_byte_parser_on_day1_input() {
+        if !has_avx512() {
+            return;
+        }
+        let input = include_bytes!("../../../input/day1.txt");
+        let expected = parse_ints_with(Backend::Ssse3, input, &crate::simd::LOOKUP_TABLE);
+        assert_eq!(expected, parse_ints(input));
+    }
+
+    proptest! {
+        #[test]
+        fn matches_scalar_parser(input in "[0-9 \n]{0,300}") {
+            if !has_avx512() {
+                return Ok(());
+            }
+            prop_assert_eq!(reference(input.as_bytes()), parse_ints(input.as_bytes()));
+        }
+
+        #[test]
+        fn matches_scalar_parser_on_any_bytes(input in prop::collection::vec(any::<u8>(), 0..300)) {
+            if !has_avx512() {
+                return Ok(());
+            }
+            prop_assert_eq!(reference(&input), parse_ints(&input));
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Parsing fixed-point decimal numbers like `3.75` and `-0.125`.
+//!
+//! The integer and fractional parts of a number are just two adjacent digit ranges
+//! with a `.` between them, so the lookup table already extracts them as two numbers.
+//! They're glued back together afterwards using where each one starts and ends.
+use std::mem::MaybeUninit;
+
+use super::{next_chunk, PatternData};
+use crate::{
+    dispatch,
+    scalar::{self, signed, Decimal},
+};
+
+/// A number that's been pushed to the output but could still get a fractional part.
+struct OpenNumber {
+    /// The offset just past its last digit.
+    end: usize,
+    negative: bool,
+    magnitude: u64,
+}
+
+/// Parses the decimal numbers out of `bytes` 16 bytes at a time.
+///
+/// A `.` with digits on both sides joins them into one number, and a `-` directly
+/// before the first digit makes it negative. Any other byte is a separator, so `3.`
+/// and `.5` parse as `3` and `5`. Numbers with more than 18 digits in total wrap.
+///
+/// On CPUs without SSSE3 this falls back to [`scalar::parse_decimals`].
+pub fn parse_decimals(bytes: &[u8], lookup_table: &[PatternData]) -> Vec<Decimal> {
+    if dispatch::has_ssse3() {
+        // SAFETY: We just checked that the CPU supports SSSE3.
+        unsafe { parse_decimals_ssse3(bytes, lookup_table) }
+    } else {
+        scalar::parse_decimals(bytes)
+    }
+}
+
+#[target_feature(enable = "ssse3")]
+unsafe fn parse_decimals_ssse3(bytes: &[u8], lookup_table: &[PatternData]) -> Vec<Decimal> {
+    let mut output: Vec<Decimal> = Vec::with_capacity(bytes.len() / 4 + 16);
+    let mut converted = [MaybeUninit::<u64>::uninit(); 16];
+    let mut open: Option<OpenNumber> = None;
+    let mut input_cursor = 0;
+    while let Some(chunk) = next_chunk(bytes, lookup_table, &mut input_cursor) {
+        let n_extracted = chunk.convert_into(bytes, &mut converted);
+        for (n, (start, len)) in converted[..n_extracted].iter().zip(chunk.spans()) {
+            let len = len as u32;
+            // SAFETY: The first `n_extracted` numbers were written by the conversion.
+            let magnitude = unsafe { n.assume_init() };
+
+            if let Some(integer) = open.take_if(|o| o.end + 1 == start && bytes[o.end] == b'.') {
+                let magnitude = integer
+                    .magnitude
+                    .wrapping_mul(10u64.wrapping_pow(len))
+                    .wrapping_add(magnitude);
+                *output.last_mut().unwrap() = Decimal {
+                    value: signed(magnitude, integer.negative),
+                    scale: len,
+                };
+                continue;
+            }
+            let negative = start > 0 && bytes[start - 1] == b'-';
+            output.push(Decimal {
+                value: signed(magnitude, negative),
+                scale: 0,
+            });
+            open = Some(OpenNumber {
+                end: start + len as usize,
+                negative,
+                magnitude,
+            });
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simd::LOOKUP_TABLE;
+    use proptest::prelude::*;
+
+    fn decimal(value: i64, scale: u32) -> Decimal {
+        Decimal { value, scale }
+    }
+
+    #[test]
+    fn joins_integer_and_fraction() {
+        let parsed = parse_decimals(b"3.75 -0.125 12 -7 1.2.3 3. .5\n", &LOOKUP_TABLE);
+        assert_eq!(
+            parsed,
+            vec![
+                decimal(375, 2),
+                decimal(-125, 3),
+                decimal(12, 0),
+                decimal(-7, 0),
+                decimal(12, 1),
+                decimal(3, 0),
+                decimal(3, 0),
+                decimal(5, 0),
+            ]
+        );
+        assert_eq!(parsed[0].to_f64(), 3.75);
+        assert_eq!(f64::from(parsed[1]), -0.125);
+    }
+
+    proptest! {
+        #[test]
+        fn matches_written_decimals(
+            nums in prop::collection::vec((any::<bool>(), 0u64..1_000_000, "[0-9]{0,6}"), 0..100),
+            seps in "[ \n,]{1,3}",
+        ) {
+            let mut written = Vec::new();
+            let mut expected = Vec::new();
+            for (negative, integer, fraction) in &nums {
+                let sign = if *negative { "-" } else { "" };
+                let mut magnitude = *integer;
+                if fraction.is_empty() {
+                    written.push(format!("{sign}{integer}"));
+                } else {
+                    written.push(format!("{sign}{integer}.{fraction}"));
+                    magnitude = magnitude * 10u64.pow(fraction.len() as u32)
+                        + fraction.parse::<u64>().unwrap();
+                }
+                expected.push(decimal(signed(magnitude, *negative), fraction.len() as u32));
+            }
+            let input = written.join(&seps) + "\n";
+            prop_assert_eq!(&crate::scalar::parse_decimals(input.as_bytes()), &expected);
+            prop_assert_eq!(parse_decimals(input.as_bytes(), &LOOKUP_TABLE), expected);
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! Storing the lanes of a converted vector as whichever integer type the caller wants.
+use std::{arch::x86_64::*, fmt, mem::MaybeUninit};
+
+use crate::scalar::UnsignedTarget;
+
+/// Room for every number a single window can produce.
+pub(crate) type WindowOutput<T> = [MaybeUninit<T>; 16];
+
+/// Stores a whole vector into `output` starting at the `index`th number.
+#[inline(always)]
+fn store_at<T>(output: &mut WindowOutput<T>, index: usize, v: __m128i) {
+    assert!((index * size_of::<T>()) + 16 <= size_of::<WindowOutput<T>>());
+    unsafe {
+        let ptr = output.as_mut_ptr().add(index) as *mut __m128i;
+        _mm_storeu_si128(ptr, v);
+    }
+}
+
+/// Whether any byte of `v` is nonzero.
+#[inline(always)]
+fn any_nonzero(v: __m128i) -> bool {
+    unsafe { _mm_movemask_epi8(_mm_cmpeq_epi8(v, _mm_setzero_si128())) != 0xFFFF }
+}
+
+/// Picks the bytes at `indices` out of `v` into the front of a vector.
+#[inline(always)]
+fn gather_bytes(v: __m128i, indices: [i8; 16]) -> __m128i {
+    unsafe { _mm_shuffle_epi8(v, _mm_loadu_si128(indices.as_ptr() as *const __m128i)) }
+}
+
+/// The low byte of each 32 bit lane, then the low two bytes of each.
+const LOW_BYTES_OF_U32S: [i8; 16] = [0, 4, 8, 12, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1];
+const LOW_U16S_OF_U32S: [i8; 16] = [0, 1, 4, 5, 8, 9, 12, 13, -1, -1, -1, -1, -1, -1, -1, -1];
+/// The same for 64 bit lanes.
+const LOW_BYTES_OF_U64S: [i8; 16] = [0, 8, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1];
+const LOW_U16S_OF_U64S: [i8; 16] = [0, 1, 8, 9, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1];
+
+/// An integer type the conversion kernels can write their results to.
+///
+/// Each method stores every lane of a converted vector as `Self` at the front of
+/// `output`, whether or not it holds a number, and returns whether any lane was too
+/// big for `Self`. Those lanes wrap around, the same as parsing one digit at a time
+/// with wrapping arithmetic would. Lanes that don't hold a number are either zero or
+/// a copy of one that does, so they never cause a false overflow.
+///
+/// 8 and 16 bit lanes come from numbers of at most 2 digits, which fit in every type.
+pub trait OutputInt: UnsignedTarget {
+    fn store_u8s(v: __m128i, output: &mut WindowOutput<Self>) -> bool;
+    fn store_u16s(v: __m128i, output: &mut WindowOutput<Self>) -> bool;
+    fn store_u32s(v: __m128i, output: &mut WindowOutput<Self>) -> bool;
+    fn store_u64s(v: __m128i, output: &mut WindowOutput<Self>) -> bool;
+}
+
+impl OutputInt for u8 {
+    #[inline(always)]
+    fn store_u8s(v: __m128i, output: &mut WindowOutput<Self>) -> bool {
+        store_at(output, 0, v);
+        false
+    }
+
+    #[inline(always)]
+    fn store_u16s(v: __m128i, output: &mut WindowOutput<Self>) -> bool {
+        // The values are at most 99, so saturating doesn't change them
+        store_at(output, 0, unsafe { _mm_packus_epi16(v, v) });
+        false
+    }
+
+    #[inline(always)]
+    fn store_u32s(v: __m128i, output: &mut WindowOutput<Self>) -> bool {
+        store_at(output, 0, gather_bytes(v, LOW_BYTES_OF_U32S));
+        any_nonzero(unsafe { _mm_srli_epi32::<8>(v) })
+    }
+
+    #[inline(always)]
+    fn store_u64s(v: __m128i, output: &mut WindowOutput<Self>) -> bool {
+        store_at(output, 0, gather_bytes(v, LOW_BYTES_OF_U64S));
+        any_nonzero(unsafe { _mm_srli_epi64::<8>(v) })
+    }
+}
+
+impl OutputInt for u16 {
+    #[inline(always)]
+    fn store_u8s(v: __m128i, output: &mut WindowOutput<Self>) -> bool {
+        unsafe {
+            let zero = _mm_setzero_si128();
+            store_at(output, 0, _mm_unpacklo_epi8(v, zero));
+            store_at(output, 8, _mm_unpackhi_epi8(v, zero));
+        }
+        false
+    }
+
+    #[inline(always)]
+    fn store_u16s(v: __m128i, output: &mut WindowOutput<Self>) -> bool {
+        store_at(output, 0, v);
+        false
+    }
+
+    #[inline(always)]
+    fn store_u32s(v: __m128i, output: &mut WindowOutput<Self>) -> bool {
+        store_at(output, 0, gather_bytes(v, LOW_U16S_OF_U32S));
+        any_nonzero(unsafe { _mm_srli_epi32::<16>(v) })
+    }
+
+    #[inline(always)]
+    fn store_u64s(v: __m128i, output: &mut WindowOutput<Self>) -> bool {
+        store_at(output, 0, gather_bytes(v, LOW_U16S_OF_U64S));
+        any_nonzero(unsafe { _mm_srli_epi64::<16>(v) })
+    }
+}
+
+impl OutputInt for u32 {
+    #[inline(always)]
+    fn store_u8s(v: __m128i, output: &mut WindowOutput<Self>) -> bool {
+        unsafe {
+            let zero = _mm_setzero_si128();
+            let lo = _mm_unpacklo_epi8(v, zero);
+            let hi = _mm_unpackhi_epi8(v, zero);
+            store_at(output, 0, _mm_unpacklo_epi16(lo, zero));
+            store_at(output, 4, _mm_unpackhi_epi16(lo, zero));
+            store_at(output, 8, _mm_unpacklo_epi16(hi, zero));
+            store_at(output, 12, _mm_unpackhi_epi16(hi, zero));
+        }
+        false
+    }
+
+    #[inline(always)]
+    fn store_u16s(v: __m128i, output: &mut WindowOutput<Self>) -> bool {
+        unsafe {
+            let zero = _mm_setzero_si128();
+            store_at(output, 0, _mm_unpacklo_epi16(v, zero));
+            store_at(output, 4, _mm_unpackhi_epi16(v, zero));
+        }
+        false
+    }
+
+    #[inline(always)]
+    fn store_u32s(v: __m128i, output: &mut WindowOutput<Self>) -> bool {
+        store_at(output, 0, v);
+        false
+    }
+
+    #[inline(always)]
+    fn store_u64s(v: __m128i, output: &mut WindowOutput<Self>) -> bool {
+        // Keep the low half of each 64 bit lane, the upper half of the vector is junk.
+        let truncated = unsafe { _mm_shuffle_epi32::<0b10_00_10_00>(v) };
+        store_at(output, 0, truncated);
+        any_nonzero(unsafe { _mm_srli_epi64::<32>(v) })
+    }
+}
+
+impl OutputInt for u64 {
+    #[inline(always)]
+    fn store_u8s(v: __m128i, output: &mut WindowOutput<Self>) -> bool {
+        unsafe {
+            let zero = _mm_setzero_si128();
+            let lo = _mm_unpacklo_epi8(v, zero);
+            let hi = _mm_unpackhi_epi8(v, zero);
+            for (i, half) in [lo, hi].into_iter().enumerate() {
+                let lo = _mm_unpacklo_epi16(half, zero);
+                let hi = _mm_unpackhi_epi16(half, zero);
+                store_at(output, 8 * i, _mm_unpacklo_epi32(lo, zero));
+                store_at(output, 8 * i + 2, _mm_unpackhi_epi32(lo, zero));
+                store_at(output, 8 * i + 4, _mm_unpacklo_epi32(hi, zero));
+                store_at(output, 8 * i + 6, _mm_unpackhi_epi32(hi, zero));
+            }
+        }
+        false
+    }
+
+    #[inline(always)]
+    fn store_u16s(v: __m128i, output: &mut WindowOutput<Self>) -> bool {
+        unsafe {
+            let zero = _mm_setzero_si128();
+            let lo = _mm_unpacklo_epi16(v, zero);
+            let hi = _mm_unpackhi_epi16(v, zero);
+            store_at(output, 0, _mm_unpacklo_epi32(lo, zero));
+            store_at(output, 2, _mm_unpackhi_epi32(lo, zero));
+            store_at(output, 4, _mm_unpacklo_epi32(hi, zero));
+            store_at(output, 6, _mm_unpackhi_epi32(hi, zero));
+        }
+        false
+    }
+
+    #[inline(always)]
+    fn store_u32s(v: __m128i, output: &mut WindowOutput<Self>) -> bool {
+        unsafe {
+            let zero = _mm_setzero_si128();
+            store_at(output, 0, _mm_unpacklo_epi32(v, zero));
+            store_at(output, 2, _mm_unpackhi_epi32(v, zero));
+        }
+        false
+    }
+
+    #[inline(always)]
+    fn store_u64s(v: __m128i, output: &mut WindowOutput<Self>) -> bool {
+        store_at(output, 0, v);
+        false
+    }
+}
+
+/// The integer types [`super::parse_ints_as`] can parse into. Each one is stored at
+/// its own width straight from the conversion, so narrow types use less memory.
+pub trait ParseTarget: OutputInt + fmt::Debug + Send + Sync + 'static {}
+
+impl ParseTarget for u8 {}
+impl ParseTarget for u16 {}
+impl ParseTarget for u32 {}
+impl ParseTarget for u64 {}
@@ -0,0 +1,204 @@
+//! Generation of the lookup table that maps a 16 bit digit pattern to the
+//! shuffle and conversion needed to parse it.
+//!
+//! This module is also compiled into the build script, which writes the table
+//! out as Rust source so that [`super::LOOKUP_TABLE`] is built at compile time.
+//! It can't depend on anything else in the crate.
+
+/// A byte of 0x80 tells the pshufb instruction to put a zero at the corresponding location.
+/// We'll use this as the basis for shuffles and set individual bytes to particular values.
+pub(crate) const ZERO_SHUFFLE: [u8; 16] = [
+    0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+];
+
+/// The location of a sequence of digits and how many digits there are.
+pub(crate) struct DigitRange {
+    pub(crate) start: usize,
+    pub(crate) size: usize,
+}
+
+/// Which span size a number of digits falls into.
+#[allow(dead_code)]
+pub(crate) fn compute_conversion_size(digits: usize) -> usize {
+    match digits {
+        0 => 0,
+        1 => 1,
+        2 => 2,
+        3 | 4 => 4,
+        5..=8 => 8,
+        9..=16 => 16,
+        _ => panic!("can't convert integers with more than 16 digits"),
+    }
+}
+
+/// How many ranges are consumable from a pattern and at what size.
+#[derive(Clone, Default)]
+pub(crate) struct ConsumableRanges {
+    /// How many ranges are consumable from the pattern.
+    pub(crate) n_ranges: usize,
+    /// At what span size should the digits be consumed.
+    pub(crate) conversion_size: usize,
+}
+
+/// Determine the ranges that are consumable from the total set of ranges
+/// extracted from a pattern.
+pub(crate) fn consumable_ranges(digit_ranges: &[DigitRange]) -> ConsumableRanges {
+    let mut biggest_span_size = 0;
+    let mut n_spans_at_biggest_size = 0;
+    for span_size in [1usize, 2, 4, 8, 16].iter() {
+        // Only so many spans of this size fit in a 16 byte vector
+        let max_spans = 16 / span_size;
+        let mut consumable_ranges_at_this_size = 0;
+        for dr in digit_ranges.iter().take(max_spans) {
+            if dr.size <= *span_size {
+                consumable_ranges_at_this_size += 1;
+            } else {
+                // Stop on the first digit range that won't fit in this span
+                break;
+            }
+        }
+        if (consumable_ranges_at_this_size > 0)
+            && (consumable_ranges_at_this_size > n_spans_at_biggest_size)
+        {
+            biggest_span_size = *span_size;
+            n_spans_at_biggest_size = consumable_ranges_at_this_size;
+        }
+    }
+    ConsumableRanges {
+        n_ranges: n_spans_at_biggest_size,
+        conversion_size: biggest_span_size,
+    }
+}
+
+/// Information about which ranges were extracted from a pattern, at what size,
+/// and whether any bits from the pattern couldn't be consumed.
+pub(crate) struct ExtractedPatternInfo {
+    pub(crate) consumable_ranges: ConsumableRanges,
+    pub(crate) digit_ranges: Vec<DigitRange>,
+    pub(crate) incomplete_bits: usize,
+}
+
+/// Determine the ranges that are extractable from a pattern and whether any bits
+/// couldn't be consumed.
+pub(crate) fn extract_pattern_info(mut pattern: u16) -> ExtractedPatternInfo {
+    let mut digit_ranges = vec![];
+    // A sequence of set bits at the end of the pattern could be an incomplete
+    // number, so we don't want to count those digits.
+    let n_trailing_bits = pattern.trailing_ones();
+    let max_len = 16;
+    let mut shifted = 0;
+    loop {
+        // We need to bring a sequence of 1 bits to the front of the pattern,
+        // so count the leading zeros and shift left by that amount.
+        let useless_zeros = pattern.leading_zeros();
+        shifted += useless_zeros;
+        if (shifted + n_trailing_bits) >= max_len {
+            break;
+        }
+        pattern <<= useless_zeros;
+        // Now that we have a sequence of set bits, count them.
+        let n_digits = pattern.leading_ones();
+        // Record the start position of the digits and how many there are.
+        digit_ranges.push(DigitRange {
+            start: shifted as usize,
+            size: n_digits as usize,
+        });
+        shifted += n_digits;
+        pattern <<= n_digits;
+    }
+    if digit_ranges.is_empty() {
+        return ExtractedPatternInfo {
+            consumable_ranges: ConsumableRanges::default(),
+            digit_ranges: Vec::new(),
+            incomplete_bits: 0,
+        };
+    }
+    let ranges_consumable = consumable_ranges(&digit_ranges);
+    let incomplete_bits = if ranges_consumable.n_ranges < digit_ranges.len() {
+        16 - digit_ranges[ranges_consumable.n_ranges].start
+    } else {
+        n_trailing_bits as usize
+    };
+
+    ExtractedPatternInfo {
+        consumable_ranges: ranges_consumable,
+        digit_ranges,
+        incomplete_bits,
+    }
+}
+
+/// From the consumable ranges of a pattern, generate a shuffle array that can be used
+/// to parse them. Note that a 0x80 byte in the shuffle array means to place a zero in
+/// the destination.
+pub(crate) fn generate_shuffle_array(pat_info: &ExtractedPatternInfo) -> [u8; 16] {
+    let mut shuffle_array = ZERO_SHUFFLE;
+    if pat_info.consumable_ranges.n_ranges == 0 {
+        return shuffle_array;
+    }
+    let conversion_size = pat_info.consumable_ranges.conversion_size;
+    for i in 0..pat_info.consumable_ranges.n_ranges {
+        let digit_range = pat_info.digit_ranges.get(i).unwrap();
+        // Put the cursor at the next block of `conversion_size` bits
+        let output_cursor = conversion_size * i;
+        // We need to pad each set of digits with leading zeros to make it a consistent
+        // number of digits for the entire vector. THE ZERO_SHUFFLE array is filled
+        // with the bytes that generate zeros already, so we need to skip ahead and
+        // only fill out the shuffle pattern bytes that correspond to the actual digits
+        // in the source array.
+        let zero_bits = conversion_size - digit_range.size;
+        for j in 0..digit_range.size {
+            let src = digit_range.start + j;
+            let dest = output_cursor + zero_bits + j;
+            // This cast to u8 should be safe because the value is an index into an array
+            // of size 16, so we should never see a number larger than that.
+            shuffle_array[dest] = src as u8;
+        }
+    }
+    shuffle_array
+}
+
+/// A lookup table entry corresponding to a 16 bit pattern.
+///
+/// This is `repr(C)` and made of bytes so that a table can be read straight out of a
+/// file, see [`super::table_file`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct PatternData {
+    /// The input array for the `pshufb` instruction
+    pub(crate) shuffle_array: [u8; 16],
+    /// How many trailing bits there are for this pattern
+    pub(crate) skip: u8,
+    /// How many numbers were extracted from this pattern
+    pub(crate) n_extracted: u8,
+    /// The conversion size for this pattern
+    pub(crate) conversion_size: u8,
+}
+
+/// How many entries there are in the lookup table, one for every 16 bit pattern.
+pub const LOOKUP_TABLE_LEN: usize = 1 << 16;
+
+/// Generate a lookup table for shuffles of every 16 bit pattern.
+pub(crate) fn generate_pattern_lookup_table() -> Vec<PatternData> {
+    let mut lookup_table = vec![];
+    for i in 0..=u16::MAX {
+        let extracted = extract_pattern_info(i);
+        if extracted.consumable_ranges.n_ranges > 16 {
+            panic!(
+                "found {} ranges from pattern {i:016b}",
+                extracted.consumable_ranges.n_ranges
+            );
+        }
+        let shuffle = generate_shuffle_array(&extracted);
+        let pattern_data = PatternData {
+            shuffle_array: shuffle,
+            // Safe conversion, more than 256 incomplete bits would mean
+            // that we're operating on 256 byte vectors of digits,
+            // but we're only operating on 16 byte vectors.
+            skip: 16 - extracted.incomplete_bits as u8,
+            n_extracted: extracted.consumable_ranges.n_ranges as u8,
+            conversion_size: extracted.consumable_ranges.conversion_size as u8,
+        };
+        lookup_table.push(pattern_data);
+    }
+    lookup_table
+}
@@ -0,0 +1,264 @@
+//! Parsing hexadecimal and binary numbers with the same lookup table as decimal ones.
+//!
+//! The table only cares where the runs of digits are, so all that changes is which
+//! bytes count as digits and how the digits are combined. Each digit is a whole number
+//! of bits, so the last two steps of the conversion shift instead of multiplying.
+use std::arch::x86_64::*;
+
+use crate::{dispatch, scalar};
+
+use super::{
+    parse_checked, shuffle_digits, IntOverflow, OutputInt, ParseTarget, PatternData, WindowOutput,
+};
+
+/// Parses the hexadecimal integers out of `bytes` 16 bytes at a time as `T`, failing
+/// on the first number that doesn't fit.
+///
+/// Both `a-f` and `A-F` are digits and any other byte is a separator, so a `#70c710`
+/// color parses as `0x70c710`. A `0x` prefix isn't understood, it parses as a `0`.
+/// On CPUs without SSSE3 this falls back to [`scalar::parse_hex_ints`].
+pub fn parse_hex_ints<T: ParseTarget>(
+    bytes: &[u8],
+    lookup_table: &[PatternData],
+) -> Result<Vec<T>, IntOverflow> {
+    if dispatch::has_ssse3() {
+        // SAFETY: We just checked that the CPU supports SSSE3.
+        unsafe { parse_hex_ints_ssse3(bytes, lookup_table) }
+    } else {
+        scalar::parse_hex_ints(bytes)
+    }
+}
+
+#[target_feature(enable = "ssse3")]
+unsafe fn parse_hex_ints_ssse3<T: ParseTarget>(
+    bytes: &[u8],
+    lookup_table: &[PatternData],
+) -> Result<Vec<T>, IntOverflow> {
+    parse_checked(
+        bytes,
+        lookup_table,
+        16,
+        detect_hex_digits,
+        |input, pat, output| convert_pow2_window(hex_digit_values(input), pat, 4, output),
+    )
+}
+
+/// Parses the binary integers out of `bytes` 16 bytes at a time as `T`, failing on
+/// the first number that doesn't fit.
+///
+/// On CPUs without SSSE3 this falls back to [`scalar::parse_bin_ints`].
+pub fn parse_bin_ints<T: ParseTarget>(
+    bytes: &[u8],
+    lookup_table: &[PatternData],
+) -> Result<Vec<T>, IntOverflow> {
+    if dispatch::has_ssse3() {
+        // SAFETY: We just checked that the CPU supports SSSE3.
+        unsafe { parse_bin_ints_ssse3(bytes, lookup_table) }
+    } else {
+        scalar::parse_bin_ints(bytes)
+    }
+}
+
+#[target_feature(enable = "ssse3")]
+unsafe fn parse_bin_ints_ssse3<T: ParseTarget>(
+    bytes: &[u8],
+    lookup_table: &[PatternData],
+) -> Result<Vec<T>, IntOverflow> {
+    parse_checked(
+        bytes,
+        lookup_table,
+        2,
+        detect_bin_digits,
+        |input, pat, output| convert_pow2_window(bin_digit_values(input), pat, 1, output),
+    )
+}
+
+/// Returns a mask of the locations of `0-9`, `a-f` and `A-F`.
+#[inline(always)]
+fn detect_hex_digits(input: __m128i) -> __m128i {
+    unsafe {
+        let decimal = super::detect_digits(input);
+        // Setting the 0x20 bit turns upper case letters into lower case ones
+        let lower = _mm_or_si128(input, _mm_set1_epi8(0x20));
+        let after_a = _mm_cmpgt_epi8(lower, _mm_set1_epi8((b'a' - 1) as i8));
+        let before_g = _mm_cmplt_epi8(lower, _mm_set1_epi8(b'g' as i8));
+        _mm_or_si128(decimal, _mm_and_si128(after_a, before_g))
+    }
+}
+
+/// Returns a mask of the locations of `0` and `1`.
+#[inline(always)]
+fn detect_bin_digits(input: __m128i) -> __m128i {
+    unsafe {
+        let zero = _mm_cmpeq_epi8(input, _mm_set1_epi8(b'0' as i8));
+        let one = _mm_cmpeq_epi8(input, _mm_set1_epi8(b'1' as i8));
+        _mm_or_si128(zero, one)
+    }
+}
+
+/// Turns hex digits into their values. The low nibble of `0-9` is already the value,
+/// and letters have the 0x40 bit set and a low nibble 9 short of theirs. Zeroed bytes
+/// stay zero.
+#[inline(always)]
+fn hex_digit_values(input: __m128i) -> __m128i {
+    unsafe {
+        let low_nibbles = _mm_and_si128(input, _mm_set1_epi8(0x0F));
+        let letter_bit = _mm_set1_epi8(0x40);
+        let letters = _mm_cmpeq_epi8(_mm_and_si128(input, letter_bit), letter_bit);
+        _mm_add_epi8(low_nibbles, _mm_and_si128(letters, _mm_set1_epi8(9)))
+    }
+}
+
+/// Turns binary digits into their values. Zeroed bytes saturate to zero.
+#[inline(always)]
+fn bin_digit_values(input: __m128i) -> __m128i {
+    unsafe { _mm_subs_epu8(input, _mm_set1_epi8(b'0' as i8)) }
+}
+
+/// Shuffles the complete numbers in a window into place and converts them from digit
+/// values of `bits` bits each, returning how many there are and whether any were too
+/// big for `T`.
+#[inline(always)]
+fn convert_pow2_window<T: OutputInt>(
+    input: __m128i,
+    pattern_data: &PatternData,
+    bits: i32,
+    output: &mut WindowOutput<T>,
+) -> (usize, bool) {
+    let digits = shuffle_digits(input, pattern_data);
+    let overflowed = unsafe {
+        let weights = _mm_set1_epi16((1 << 8 | 1 << bits) as i16);
+        let two_digits = _mm_maddubs_epi16(digits, weights);
+        let weights = _mm_set1_epi32(1 << 16 | 1 << (2 * bits));
+        let four_digits = _mm_madd_epi16(two_digits, weights);
+        // Four hex digits can be too big for the signed pack the decimal conversion
+        // uses, so the last two steps shift each pair together instead. The odd
+        // lanes are left with junk, which the shuffle replaces with copies.
+        let shift = _mm_cvtsi32_si128(4 * bits);
+        let pairs = _mm_or_si128(
+            _mm_sll_epi32(four_digits, shift),
+            _mm_srli_epi64::<32>(four_digits),
+        );
+        let eight_digits = _mm_shuffle_epi32::<0b10_00_10_00>(pairs);
+        match pattern_data.conversion_size {
+            0 => false,
+            1 => T::store_u8s(digits, output),
+            2 => T::store_u16s(two_digits, output),
+            4 => T::store_u32s(four_digits, output),
+            8 => T::store_u32s(eight_digits, output),
+            16 => {
+                let halves = _mm_unpacklo_epi32(eight_digits, _mm_setzero_si128());
+                let shift = _mm_cvtsi32_si128(8 * bits);
+                let sixteen_digits =
+                    _mm_or_si128(_mm_sll_epi64(halves, shift), _mm_srli_si128::<8>(halves));
+                T::store_u64s(_mm_unpacklo_epi64(sixteen_digits, sixteen_digits), output)
+            }
+            _ => panic!("invalid conversion size: {}", pattern_data.conversion_size),
+        }
+    };
+    (pattern_data.n_extracted as usize, overflowed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simd::LOOKUP_TABLE;
+    use proptest::prelude::*;
+
+    #[test]
+    fn parses_hex_colors() {
+        let input = b"#70c710 #FFFFFF\n#000000,#1A2b3C\n";
+        assert_eq!(
+            parse_hex_ints::<u32>(input, &LOOKUP_TABLE),
+            Ok(vec![0x70c710, 0xffffff, 0, 0x1a2b3c])
+        );
+        assert_eq!(
+            parse_hex_ints::<u16>(input, &LOOKUP_TABLE),
+            Err(IntOverflow {
+                offset: 1,
+                max: u16::MAX.into()
+            })
+        );
+    }
+
+    #[test]
+    fn parses_bit_strings() {
+        let input = b"0 1 10 1011 11111111 111111111111111\n";
+        assert_eq!(
+            parse_bin_ints::<u16>(input, &LOOKUP_TABLE),
+            Ok(vec![0, 1, 2, 11, 255, 0x7fff])
+        );
+        let long = format!("1 {:b} {:064b}\n", u64::MAX, 0x8000_0000_0000_0001u64);
+        assert_eq!(
+            parse_bin_ints::<u64>(long.as_bytes(), &LOOKUP_TABLE),
+            Ok(vec![1, u64::MAX, 0x8000_0000_0000_0001])
+        );
+        let too_long = format!("1 1{:064b}\n", 0);
+        assert_eq!(
+            parse_bin_ints::<u64>(too_long.as_bytes(), &LOOKUP_TABLE),
+            Err(IntOverflow {
+                offset: 2,
+                max: u64::MAX
+            })
+        );
+        assert_eq!(
+            parse_bin_ints::<u8>(input, &LOOKUP_TABLE),
+            Err(IntOverflow {
+                offset: 21,
+                max: u8::MAX.into()
+            })
+        );
+    }
+
+    /// Joins `nums` written by `format` in `radix` with spaces, parses them back, and
+    /// checks that against `u64::from_str_radix`.
+    fn check_radix(
+        nums: &[u64],
+        format: impl Fn(u64) -> String,
+        radix: u32,
+    ) -> Result<(), TestCaseError> {
+        let parse = match radix {
+            16 => parse_hex_ints::<u64>,
+            2 => parse_bin_ints::<u64>,
+            _ => unreachable!(),
+        };
+        let written: Vec<String> = nums.iter().map(|n| format(*n)).collect();
+        let input = written.join(" ") + "\n";
+        let expected: Vec<u64> = written
+            .iter()
+            .map(|n| u64::from_str_radix(n, radix).unwrap())
+            .collect();
+        let parse_scalar = match radix {
+            16 => crate::scalar::parse_hex_ints::<u64>,
+            _ => crate::scalar::parse_bin_ints::<u64>,
+        };
+        prop_assert_eq!(parse_scalar(input.as_bytes()), Ok(expected.clone()));
+        prop_assert_eq!(parse(input.as_bytes(), &LOOKUP_TABLE), Ok(expected));
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn hex_matches_from_str_radix(nums in prop::collection::vec(any::<u64>(), 0..100), upper in any::<bool>()) {
+            let format = |n: u64| if upper { format!("{n:X}") } else { format!("{n:x}") };
+            check_radix(&nums, format, 16)?;
+        }
+
+        #[test]
+        fn short_hex_matches_from_str_radix(nums in prop::collection::vec(0u64..1 << 32, 0..100)) {
+            check_radix(&nums, |n| format!("{n:08x}"), 16)?;
+        }
+
+        #[test]
+        fn bin_matches_from_str_radix(nums in prop::collection::vec(any::<u64>(), 0..100)) {
+            check_radix(&nums, |n| format!("{n:b}"), 2)?;
+        }
+
+        #[test]
+        fn narrow_hex_matches_from_str_radix(nums in prop::collection::vec(0u64..1 << 32, 0..100)) {
+            let input = nums.iter().map(|n| format!("{n:x}")).collect::<Vec<_>>().join(",");
+            let expected: Vec<u32> = nums.iter().map(|n| *n as u32).collect();
+            prop_assert_eq!(parse_hex_ints::<u32>(input.as_bytes(), &LOOKUP_TABLE), Ok(expected));
+        }
+    }
+}
@@ -0,0 +1,328 @@
+//! A file format for swapping lookup tables between experiments.
+//!
+//! The file is a fixed size header followed by the entries, each one a [`PatternData`]
+//! laid out field by field:
+//!
+//! | offset | size | contents                                  |
+//! |--------|------|-------------------------------------------|
+//! | 0      | 8    | [`MAGIC`]                                 |
+//! | 8      | 4    | format version, [`FORMAT_VERSION`]        |
+//! | 12     | 4    | entry size in bytes, [`ENTRY_SIZE`]       |
+//! | 16     | 4    | entry count, [`LOOKUP_TABLE_LEN`]         |
+//! | 20     | 4    | FNV-1a checksum of the entries            |
+//! | 24     | ...  | the entries                               |
+//!
+//! All integers are little endian. Every field is checked on load, as is every entry,
+//! so a stale or truncated table is an error rather than garbage output.
+use std::{fmt, io, path::Path};
+
+use super::pattern::{PatternData, LOOKUP_TABLE_LEN};
+
+/// The bytes every lookup table file starts with.
+pub const MAGIC: [u8; 8] = *b"PINTSLUT";
+
+/// The version of the format written by [`write_lookup_table`], bump this whenever
+/// the meaning of an entry changes.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// How many bytes each entry takes up in the file.
+pub const ENTRY_SIZE: usize = 19;
+
+/// How many bytes come before the entries.
+pub const HEADER_SIZE: usize = 24;
+
+// Loading without copying reinterprets the entries in place, which is only sound
+// if the struct is exactly the bytes in the file with no alignment requirement.
+const _: () = assert!(size_of::<PatternData>() == ENTRY_SIZE);
+const _: () = assert!(align_of::<PatternData>() == 1);
+
+/// The reasons a lookup table can fail to load.
+#[derive(Debug)]
+pub enum LookupTableError {
+    /// The file couldn't be read or written.
+    Io(io::Error),
+    /// There are fewer bytes than the header needs.
+    TooShort { len: usize },
+    /// The file doesn't start with [`MAGIC`].
+    BadMagic { found: [u8; 8] },
+    /// The file was written by a different version of the format.
+    UnsupportedVersion { found: u32 },
+    /// The entries in the file aren't the size of a [`PatternData`].
+    EntrySizeMismatch { found: u32 },
+    /// The table doesn't have an entry for every 16 bit pattern.
+    EntryCountMismatch { found: u32 },
+    /// The number of bytes after the header doesn't match the header.
+    LengthMismatch { expected: usize, found: usize },
+    /// The entries don't match the checksum in the header.
+    ChecksumMismatch { expected: u32, found: u32 },
+    /// An entry would make the parser misbehave.
+    InvalidEntry { index: usize },
+}
+
+impl fmt::Display for LookupTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to access lookup table: {e}"),
+            Self::TooShort { len } => write!(
+                f,
+                "lookup table is {len} bytes, too short for the {HEADER_SIZE} byte header"
+            ),
+            Self::BadMagic { found } => {
+                write!(f, "not a lookup table, expected magic {MAGIC:?} but found {found:?}")
+            }
+            Self::UnsupportedVersion { found } => write!(
+                f,
+                "lookup table has format version {found}, only version {FORMAT_VERSION} is supported"
+            ),
+            Self::EntrySizeMismatch { found } => write!(
+                f,
+                "lookup table has {found} byte entries, expected {ENTRY_SIZE} byte entries"
+            ),
+            Self::EntryCountMismatch { found } => write!(
+                f,
+                "lookup table has {found} entries, expected {LOOKUP_TABLE_LEN} entries"
+            ),
+            Self::LengthMismatch { expected, found } => write!(
+                f,
+                "lookup table should have {expected} bytes of entries but has {found}"
+            ),
+            Self::ChecksumMismatch { expected, found } => write!(
+                f,
+                "lookup table checksum is {found:#010x}, header says {expected:#010x}"
+            ),
+            Self::InvalidEntry { index } => {
+                write!(f, "lookup table entry for pattern {index:016b} is invalid")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LookupTableError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LookupTableError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// 32 bit FNV-1a, which is plenty to catch a table that was truncated or overwritten.
+fn checksum(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c9dc5, |hash, &byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x01000193)
+    })
+}
+
+/// Checks that an entry can't send the parser out of bounds or stop it making progress.
+fn is_valid_entry(entry: &PatternData) -> bool {
+    let shuffle_ok = entry
+        .shuffle_array
+        .iter()
+        .all(|&idx| idx < 16 || idx == 0x80);
+    let sizes_ok = match entry.conversion_size {
+        0 => entry.n_extracted == 0,
+        1 | 2 | 4 | 8 | 16 => {
+            usize::from(entry.n_extracted) * usize::from(entry.conversion_size) <= 16
+        }
+        _ => false,
+    };
+    let skip_ok = entry.skip <= 16 && (entry.n_extracted == 0 || entry.skip > 0);
+    shuffle_ok && sizes_ok && skip_ok
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+/// Serializes a lookup table into the file format.
+pub fn encode_lookup_table(table: &[PatternData]) -> Vec<u8> {
+    let mut entries = Vec::with_capacity(table.len() * ENTRY_SIZE);
+    for entry in table {
+        entries.extend_from_slice(&entry.shuffle_array);
+        entries.extend_from_slice(&[entry.skip, entry.n_extracted, entry.conversion_size]);
+    }
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + entries.len());
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&(ENTRY_SIZE as u32).to_le_bytes());
+    bytes.extend_from_slice(&(table.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&checksum(&entries).to_le_bytes());
+    bytes.extend_from_slice(&entries);
+    bytes
+}
+
+/// Validates a serialized lookup table and borrows the entries without copying them.
+pub fn lookup_table_from_bytes(bytes: &[u8]) -> Result<&[PatternData], LookupTableError> {
+    if bytes.len() < HEADER_SIZE {
+        return Err(LookupTableError::TooShort { len: bytes.len() });
+    }
+    let (header, entries) = bytes.split_at(HEADER_SIZE);
+    let magic: [u8; 8] = header[..8].try_into().unwrap();
+    if magic != MAGIC {
+        return Err(LookupTableError::BadMagic { found: magic });
+    }
+    let version = read_u32(header, 8);
+    if version != FORMAT_VERSION {
+        return Err(LookupTableError::UnsupportedVersion { found: version });
+    }
+    let entry_size = read_u32(header, 12);
+    if entry_size as usize != ENTRY_SIZE {
+        return Err(LookupTableError::EntrySizeMismatch { found: entry_size });
+    }
+    let entry_count = read_u32(header, 16);
+    if entry_count as usize != LOOKUP_TABLE_LEN {
+        return Err(LookupTableError::EntryCountMismatch { found: entry_count });
+    }
+    let expected_len = LOOKUP_TABLE_LEN * ENTRY_SIZE;
+    if entries.len() != expected_len {
+        return Err(LookupTableError::LengthMismatch {
+            expected: expected_len,
+            found: entries.len(),
+        });
+    }
+    let expected_checksum = read_u32(header, 20);
+    let found_checksum = checksum(entries);
+    if found_checksum != expected_checksum {
+        return Err(LookupTableError::ChecksumMismatch {
+            expected: expected_checksum,
+            found: found_checksum,
+        });
+    }
+    // SAFETY: `PatternData` is `repr(C)`, made only of `u8`s, and checked above to be
+    // `ENTRY_SIZE` bytes with an alignment of 1, so any `ENTRY_SIZE` bytes are a valid
+    // entry. We've checked there are exactly `LOOKUP_TABLE_LEN` of them.
+    let table = unsafe {
+        std::slice::from_raw_parts(entries.as_ptr() as *const PatternData, LOOKUP_TABLE_LEN)
+    };
+    if let Some(index) = table.iter().position(|entry| !is_valid_entry(entry)) {
+        return Err(LookupTableError::InvalidEntry { index });
+    }
+    Ok(table)
+}
+
+/// Writes a lookup table to the provided path.
+pub fn write_lookup_table(table: &[PatternData], path: &Path) -> Result<(), LookupTableError> {
+    std::fs::write(path, encode_lookup_table(table))?;
+    Ok(())
+}
+
+/// Loads and validates the lookup table at the provided path.
+pub fn load_lookup_table_from_disk(path: &Path) -> Result<Vec<PatternData>, LookupTableError> {
+    let bytes = std::fs::read(path)?;
+    Ok(lookup_table_from_bytes(&bytes)?.to_vec())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simd::LOOKUP_TABLE;
+
+    /// Recomputes the checksum so that a test can corrupt an entry without tripping it.
+    fn fix_checksum(bytes: &mut [u8]) {
+        let sum = checksum(&bytes[HEADER_SIZE..]);
+        bytes[20..24].copy_from_slice(&sum.to_le_bytes());
+    }
+
+    #[test]
+    fn loads_lookup_table_properly() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("lookup_table.dat");
+        write_lookup_table(&LOOKUP_TABLE, &path).unwrap();
+        let loaded = load_lookup_table_from_disk(&path).unwrap();
+        assert_eq!(LOOKUP_TABLE.as_slice(), loaded);
+    }
+
+    #[test]
+    fn borrows_entries_without_copying() {
+        let bytes = encode_lookup_table(&LOOKUP_TABLE);
+        let table = lookup_table_from_bytes(&bytes).unwrap();
+        assert_eq!(table.as_ptr() as *const u8, bytes[HEADER_SIZE..].as_ptr());
+        assert_eq!(table, LOOKUP_TABLE.as_slice());
+    }
+
+    #[test]
+    fn generated_entries_are_valid() {
+        assert!(LOOKUP_TABLE.iter().all(is_valid_entry));
+    }
+
+    #[test]
+    fn rejects_truncated_table() {
+        let bytes = encode_lookup_table(&LOOKUP_TABLE);
+        assert!(matches!(
+            lookup_table_from_bytes(&bytes[..10]),
+            Err(LookupTableError::TooShort { len: 10 })
+        ));
+        assert!(matches!(
+            lookup_table_from_bytes(&bytes[..bytes.len() - 1]),
+            Err(LookupTableError::LengthMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_header() {
+        let good = encode_lookup_table(&LOOKUP_TABLE);
+
+        let mut bytes = good.clone();
+        bytes[0] = b'X';
+        assert!(matches!(
+            lookup_table_from_bytes(&bytes),
+            Err(LookupTableError::BadMagic { .. })
+        ));
+
+        let mut bytes = good.clone();
+        bytes[8..12].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            lookup_table_from_bytes(&bytes),
+            Err(LookupTableError::UnsupportedVersion { .. })
+        ));
+
+        let mut bytes = good.clone();
+        bytes[12..16].copy_from_slice(&20u32.to_le_bytes());
+        assert!(matches!(
+            lookup_table_from_bytes(&bytes),
+            Err(LookupTableError::EntrySizeMismatch { found: 20 })
+        ));
+
+        let table = &LOOKUP_TABLE[..1024];
+        assert!(matches!(
+            lookup_table_from_bytes(&encode_lookup_table(table)),
+            Err(LookupTableError::EntryCountMismatch { found: 1024 })
+        ));
+    }
+
+    #[test]
+    fn rejects_corrupted_entries() {
+        let good = encode_lookup_table(&LOOKUP_TABLE);
+
+        let mut bytes = good.clone();
+        bytes[HEADER_SIZE + 100] ^= 1;
+        assert!(matches!(
+            lookup_table_from_bytes(&bytes),
+            Err(LookupTableError::ChecksumMismatch { .. })
+        ));
+
+        // A conversion size that doesn't exist, with a checksum that matches.
+        let mut bytes = good.clone();
+        bytes[HEADER_SIZE + 5 * ENTRY_SIZE + 18] = 3;
+        fix_checksum(&mut bytes);
+        assert!(matches!(
+            lookup_table_from_bytes(&bytes),
+            Err(LookupTableError::InvalidEntry { index: 5 })
+        ));
+    }
+
+    #[test]
+    fn describes_errors() {
+        let err = lookup_table_from_bytes(&[0; 4]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "lookup table is 4 bytes, too short for the 24 byte header"
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Parsing integers out of a reader without loading the whole input into memory.
+use std::io::{self, Read};
+
+use crate::simd::parse_ints_default;
+
+/// How many bytes are pulled from the reader at a time by [`parse_ints_from_reader`].
+pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Parses the integers out of everything `reader` produces, calling `f` with each one
+/// in order.
+pub fn parse_ints_from_reader<R: Read>(reader: R, f: impl FnMut(u32)) -> io::Result<()> {
+    parse_ints_from_reader_with_chunk_size(reader, DEFAULT_CHUNK_SIZE, f)
+}
+
+/// Parses the integers out of everything `reader` produces, pulling `chunk_size` bytes
+/// at a time.
+///
+/// The digits after the last separator in a chunk could be the start of a number that
+/// continues in the next read, so they're carried over to the front of the next chunk
+/// rather than parsed.
+pub fn parse_ints_from_reader_with_chunk_size<R: Read>(
+    mut reader: R,
+    chunk_size: usize,
+    mut f: impl FnMut(u32),
+) -> io::Result<()> {
+    assert!(chunk_size > 0, "chunk size must be non-zero");
+    let mut buffer = Vec::with_capacity(2 * chunk_size);
+    loop {
+        let n_carried = buffer.len();
+        buffer.resize(n_carried + chunk_size, 0);
+        let n_read = match reader.read(&mut buffer[n_carried..]) {
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {
+                buffer.truncate(n_carried);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        buffer.truncate(n_carried + n_read);
+        if n_read == 0 {
+            // Nothing else is coming, so whatever was carried over is a whole number.
+            parse_ints_default(&buffer).into_iter().for_each(&mut f);
+            return Ok(());
+        }
+        // The carried over bytes are all digits, so only the new ones can hold a
+        // separator. If there isn't one we can't tell where the number ends yet.
+        let new_bytes = &buffer[n_carried..];
+        let Some(last_separator) = new_bytes.iter().rposition(|b| !b.is_ascii_digit()) else {
+            continue;
+        };
+        let n_complete = n_carried + last_separator + 1;
+        parse_ints_default(&buffer[..n_complete])
+            .into_iter()
+            .for_each(&mut f);
+        buffer.drain(..n_complete);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scalar;
+    use proptest::prelude::*;
+
+    /// A reader that hands out at most `max_read` bytes per call, to force numbers to
+    /// straddle reads.
+    struct TrickleReader<'a> {
+        bytes: &'a [u8],
+        max_read: usize,
+    }
+
+    impl Read for TrickleReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.max_read).min(self.bytes.len());
+            buf[..n].copy_from_slice(&self.bytes[..n]);
+            self.bytes = &self.bytes[n..];
+            Ok(n)
+        }
+    }
+
+    fn collect(bytes: &[u8], chunk_size: usize, max_read: usize) -> Vec<u32> {
+        let reader = TrickleReader { bytes, max_read };
+        let mut output = vec![];
+        parse_ints_from_reader_with_chunk_size(reader, chunk_size, |n| output.push(n)).unwrap();
+        output
+    }
+
+    #[test]
+    fn streams_puzzle_input() {
+        let input = include_bytes!("../../input/day1.txt");
+        let expected = scalar::parse_ints(input);
+        let mut output = vec![];
+        parse_ints_from_reader(input.as_slice(), |n| output.push(n)).unwrap();
+        assert_eq!(output, expected);
+        assert_eq!(collect(input, 100, 37), expected);
+    }
+
+    #[test]
+    fn carries_numbers_across_chunks() {
+        let input = b"1234 5678 90 12345\n";
+        for chunk_size in 1..input.len() + 2 {
+            assert_eq!(collect(input, chunk_size, 3), vec![1234, 5678, 90, 12345]);
+        }
+    }
+
+    #[test]
+    fn carries_long_digit_runs() {
+        // Rescanning the carried digits on every read would make this quadratic
+        let mut input = vec![b'7'; 200_000];
+        input.extend_from_slice(b" 42\n");
+        assert_eq!(collect(&input, 16, 5), scalar::parse_ints(&input));
+    }
+
+    #[test]
+    fn handles_number_at_end_of_input() {
+        assert_eq!(collect(b"12 34", 3, 3), vec![12, 34]);
+        assert_eq!(collect(b"", 3, 3), Vec::<u32>::new());
+    }
+
+    proptest! {
+        #[test]
+        fn streaming_matches_whole_input(
+            nums in prop::collection::vec(0u32..=9999, 0..200),
+            chunk_size in 1usize..64,
+            max_read in 1usize..64,
+        ) {
+            let input: String = nums.iter().map(|n| format!("{n} ")).collect();
+            prop_assert_eq!(collect(input.as_bytes(), chunk_size, max_read), nums);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Checks that the caller-provided buffer APIs don't allocate once they're warmed up.
+//!
+//! This lives in its own test binary since it needs to replace the global allocator.
+#![cfg(target_arch = "x86_64")]
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use parse_ints::simd::{parse_ints_into, parse_ints_to_slice, LOOKUP_TABLE};
+
+/// Counts the allocations made by each thread, so the test harness doesn't interfere.
+struct CountingAllocator;
+
+thread_local! {
+    static N_ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        N_ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        N_ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn n_allocations() -> usize {
+    N_ALLOCATIONS.with(|n| n.get())
+}
+
+#[test]
+fn hot_loop_does_not_allocate() {
+    if !is_x86_feature_detected!("ssse3") {
+        return;
+    }
+    let inputs: [&[u8]; 3] = [
+        include_bytes!("../../input/day1.txt"),
+        b"",
+        b"1 22 333 4444 55555 666666 7777777 88888888\n",
+    ];
+    let mut vec_output = Vec::new();
+    let mut slice_output = vec![0; 4096];
+    // Let the vector grow to fit the biggest input
+    for input in inputs {
+        vec_output.clear();
+        parse_ints_into(input, &LOOKUP_TABLE, &mut vec_output);
+    }
+
+    let before = n_allocations();
+    for _ in 0..100 {
+        for input in inputs {
+            vec_output.clear();
+            parse_ints_into(input, &LOOKUP_TABLE, &mut vec_output);
+            parse_ints_to_slice(input, &LOOKUP_TABLE, &mut slice_output).unwrap();
+        }
+    }
+    assert_eq!(n_allocations() - before, 0);
+    assert_eq!(vec_output.len(), 8);
+}
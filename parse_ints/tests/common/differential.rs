@@ -0,0 +1,91 @@
+//! Compares the parsers against the obvious one-number-at-a-time implementation.
+//!
+//! This is shared by the `differential` test and the `differential` fuzz target.
+// Each of them only uses some of the checks.
+#![allow(dead_code)]
+use std::fmt;
+
+use parse_ints::{scalar, simd};
+
+/// Splits `bytes` on every non-digit and parses each run of digits, wrapping like the
+/// parsers do when a number doesn't fit in a `u32`.
+pub fn reference_parse(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .split(|b| !b.is_ascii_digit())
+        .filter(|run| !run.is_empty())
+        .map(|run| {
+            run.iter().fold(0u32, |n, d| {
+                n.wrapping_mul(10).wrapping_add(u32::from(d - b'0'))
+            })
+        })
+        .collect()
+}
+
+/// A parser that gave a different answer than [`reference_parse`].
+pub struct Disagreement {
+    pub parser: &'static str,
+    pub input: Vec<u8>,
+    pub expected: Vec<u32>,
+    pub found: Vec<u32>,
+}
+
+impl fmt::Debug for Disagreement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} disagrees with the reference", self.parser)?;
+        writeln!(f, "   input: {:?}", String::from_utf8_lossy(&self.input))?;
+        writeln!(f, "expected: {:?}", self.expected)?;
+        write!(f, "   found: {:?}", self.found)
+    }
+}
+
+fn compare(
+    parser: &'static str,
+    input: &[u8],
+    expected: &[u32],
+    found: Vec<u32>,
+) -> Result<(), Disagreement> {
+    if found == expected {
+        return Ok(());
+    }
+    Err(Disagreement {
+        parser,
+        input: input.to_vec(),
+        expected: expected.to_vec(),
+        found,
+    })
+}
+
+/// Checks [`scalar::parse_ints`] against [`reference_parse`].
+pub fn check_scalar(bytes: &[u8]) -> Result<(), Disagreement> {
+    let found = scalar::parse_ints(bytes);
+    compare("scalar::parse_ints", bytes, &reference_parse(bytes), found)
+}
+
+/// Checks [`simd::parse_ints`] against [`reference_parse`], if this CPU can run it.
+pub fn check_simd(bytes: &[u8]) -> Result<(), Disagreement> {
+    if !is_x86_feature_detected!("ssse3") {
+        return Ok(());
+    }
+    let found = simd::parse_ints(bytes, &simd::LOOKUP_TABLE);
+    compare("simd::parse_ints", bytes, &reference_parse(bytes), found)
+}
+
+/// Checks [`scalar::parse_until_newline`] against [`reference_parse`] on the first line.
+pub fn check_parse_until_newline(bytes: &[u8]) -> Result<(), Disagreement> {
+    let first_line = bytes.split(|b| *b == b'\n').next().unwrap();
+    let found = scalar::parse_until_newline(bytes);
+    compare(
+        "scalar::parse_until_newline",
+        bytes,
+        &reference_parse(first_line),
+        found,
+    )
+}
+
+/// Runs every parser on `bytes`, returning the first one that disagrees with
+/// [`reference_parse`].
+pub fn check(bytes: &[u8]) -> Result<(), Disagreement> {
+    check_scalar(bytes)?;
+    check_simd(bytes)?;
+    check_parse_until_newline(bytes)
+}
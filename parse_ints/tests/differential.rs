@@ -0,0 +1,46 @@
+//! Deterministic version of the `differential` fuzz target, so that `cargo test` runs
+//! the same checks on random inputs.
+#![cfg(target_arch = "x86_64")]
+use proptest::{prelude::*, test_runner::FileFailurePersistence};
+
+#[path = "common/differential.rs"]
+mod differential;
+
+use differential::Disagreement;
+
+/// Arbitrary bytes, and bytes that are mostly numbers so there are lots of them.
+fn inputs() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        prop::collection::vec(any::<u8>(), 0..200),
+        "[0-9 \n,]{0,200}".prop_map(String::into_bytes),
+        prop::collection::vec(prop_oneof![Just(b' '), Just(b'\n'), b'0'..=b'9'], 0..200),
+    ]
+}
+
+fn check(result: Result<(), Disagreement>) -> Result<(), TestCaseError> {
+    result.map_err(|e| TestCaseError::fail(format!("{e:?}")))
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        failure_persistence: Some(Box::new(FileFailurePersistence::Direct(
+            "proptest-regressions/differential.txt",
+        ))),
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn scalar_matches_reference(bytes in inputs()) {
+        check(differential::check_scalar(&bytes))?;
+    }
+
+    #[test]
+    fn simd_matches_reference(bytes in inputs()) {
+        check(differential::check_simd(&bytes))?;
+    }
+
+    #[test]
+    fn parse_until_newline_matches_reference(bytes in inputs()) {
+        check(differential::check_parse_until_newline(&bytes))?;
+    }
+}